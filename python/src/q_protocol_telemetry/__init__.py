@@ -1,4 +1,5 @@
+from .canonical import canonical_json, canonical_json_excluding
 from .swarm_registry import SwarmRegistry
 
-__all__ = ["SwarmRegistry"]
+__all__ = ["SwarmRegistry", "canonical_json", "canonical_json_excluding"]
 __version__ = "0.1.0"
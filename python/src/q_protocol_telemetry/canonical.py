@@ -0,0 +1,127 @@
+"""
+Canonical JSON encoding for Q Protocol content hashes.
+
+Byte-for-byte compatible with the Rust ``q_protocol::canonical`` module.
+The encoding is derived from RFC 8785 (JCS) but is not JCS: keys sort by
+code point rather than UTF-16 unit, integers beyond 2**53 are written
+verbatim, and ``None`` members are dropped.
+
+
+- no whitespace, object keys sorted by Unicode code point
+- members whose value is ``None`` are omitted
+- strings escape only ``"``, ``\\`` and control characters
+- integers verbatim; floats in ECMAScript Number-to-string form
+  (``1.0`` -> ``1``, ``1e-7``, ``1e+21``); NaN/Infinity encode as ``null``
+- ``bytes`` values are standard padded base64 strings
+
+Shared test vectors: ``definitions/canonical_vectors.json``
+(``tests/test_canonical.py``).
+Hash the result with BLAKE3 (``pip install blake3``) to get a content hash.
+"""
+
+import base64
+import json
+import math
+from typing import Any, Iterable
+
+__all__ = ["canonical_json", "canonical_json_excluding"]
+
+
+def canonical_json(value: Any) -> bytes:
+    """Canonical UTF-8 encoding of a JSON-like value."""
+    return "".join(_encode(value)).encode("utf-8")
+
+
+def canonical_json_excluding(value: dict, exclude: Iterable[str]) -> bytes:
+    """Canonical encoding of ``value`` without the named top-level keys."""
+    skip = set(exclude)
+    return canonical_json({k: v for k, v in value.items() if k not in skip})
+
+
+def _encode(value: Any) -> Iterable[str]:
+    if value is None:
+        yield "null"
+    elif value is True:
+        yield "true"
+    elif value is False:
+        yield "false"
+    elif isinstance(value, int):
+        yield str(value)
+    elif isinstance(value, float):
+        yield _format_float(value)
+    elif isinstance(value, str):
+        yield _string(value)
+    elif isinstance(value, (bytes, bytearray)):
+        yield _string(base64.b64encode(bytes(value)).decode("ascii"))
+    elif isinstance(value, dict):
+        members = sorted(
+            ((k, v) for k, v in value.items() if v is not None and not _is_nonfinite(v)),
+            key=lambda kv: kv[0],
+        )
+        yield "{"
+        for i, (key, item) in enumerate(members):
+            if i:
+                yield ","
+            yield _string(key)
+            yield ":"
+            yield from _encode(item)
+        yield "}"
+    elif isinstance(value, (list, tuple)):
+        yield "["
+        for i, item in enumerate(value):
+            if i:
+                yield ","
+            yield from _encode(item)
+        yield "]"
+    else:
+        raise TypeError(f"not canonically encodable: {type(value).__name__}")
+
+
+def _is_nonfinite(value: Any) -> bool:
+    return isinstance(value, float) and not math.isfinite(value)
+
+
+def _string(s: str) -> str:
+    return json.dumps(s, ensure_ascii=False)
+
+
+def _format_float(f: float) -> str:
+    if not math.isfinite(f):
+        return "null"
+    if f == 0.0:
+        return "0"
+
+    digits, n = _shortest_digits(abs(f))
+    k = len(digits)
+
+    if k <= n <= 21:
+        body = digits + "0" * (n - k)
+    elif 0 < n <= 21:
+        body = digits[:n] + "." + digits[n:]
+    elif -6 < n <= 0:
+        body = "0." + "0" * (-n) + digits
+    else:
+        fraction = "." + digits[1:] if k > 1 else ""
+        e = n - 1
+        body = f"{digits[0]}{fraction}e{'-' if e < 0 else '+'}{abs(e)}"
+
+    return "-" + body if f < 0 else body
+
+
+def _shortest_digits(f: float):
+    """Shortest round-trip significant digits and decimal point position."""
+    text = repr(f)
+    if "e" in text:
+        mantissa, exp = text.split("e")
+        exp = int(exp)
+    else:
+        mantissa, exp = text, 0
+    if "." in mantissa:
+        whole, frac = mantissa.split(".")
+    else:
+        whole, frac = mantissa, ""
+    digits = (whole + frac).lstrip("0")
+    leading = len(whole + frac) - len(digits)
+    n = len(whole) - leading + exp
+    digits = digits.rstrip("0") or "0"
+    return digits, n
@@ -0,0 +1,63 @@
+"""
+Shared canonical JSON vectors (``definitions/canonical_vectors.json``),
+the same ones the Rust ``canonical`` tests check.
+
+Run from ``python/``: ``python -m unittest discover tests``
+"""
+
+import json
+import sys
+import unittest
+from pathlib import Path
+
+ROOT = Path(__file__).resolve().parents[2]
+sys.path.insert(0, str(ROOT / "python" / "src"))
+
+from q_protocol_telemetry.canonical import canonical_json, canonical_json_excluding  # noqa: E402
+
+try:
+    import blake3
+except ImportError:  # optional dependency
+    blake3 = None
+
+VECTORS = ROOT / "definitions" / "canonical_vectors.json"
+
+# Fields each typed vector's content hash leaves out, as in the Rust
+# `canonical_bytes()` of that type
+EXCLUDED = {
+    "value": [],
+    "Receipt": ["content_hash", "signature"],
+    "Cube": ["content_hash"],
+    "QMemHeader": [],
+    "QMemReceipt": ["hash", "signature"],
+    "QMemState": ["hash"],
+    "QMemCoordinate": [],
+}
+
+
+class CanonicalVectorsTest(unittest.TestCase):
+    @classmethod
+    def setUpClass(cls):
+        cls.vectors = json.loads(VECTORS.read_text(encoding="utf-8"))
+
+    def encode(self, vector):
+        excluded = EXCLUDED[vector["type"]]
+        if excluded:
+            return canonical_json_excluding(vector["input"], excluded)
+        return canonical_json(vector["input"])
+
+    def test_canonical_bytes(self):
+        self.assertTrue(self.vectors)
+        for vector in self.vectors:
+            with self.subTest(vector["name"]):
+                self.assertEqual(self.encode(vector).decode("utf-8"), vector["canonical"])
+
+    @unittest.skipIf(blake3 is None, "blake3 not installed")
+    def test_blake3(self):
+        for vector in self.vectors:
+            with self.subTest(vector["name"]):
+                self.assertEqual(blake3.blake3(self.encode(vector)).hexdigest(), vector["blake3"])
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,710 @@
+// a2ac.rs
+// A2AC(md) expression grammar, parser and AST
+//
+// Grammar (see definitions/a2ac_spec.md):
+//
+//   chain      := "◈" expr ( "→" [ "◈" ] expr | "→" "ERROR" reason [ retry ] )*
+//   expr       := receipt | query | error | coordinate
+//   coordinate := subject ":" action [ ":" context ]
+//   receipt    := "RECEIPT:" id
+//   query      := "MEM:QUERY:" pattern [ "→" aggregate ] [ cmp n ]
+//   error      := "ERROR:" operation "→" reason [ retry ]
+//   retry      := "→" [ "◈" ] "RETRY:" id | "◈" "RETRY:" id
+//
+// Subjects and actions stop at ':' or whitespace; contexts, patterns and
+// operations may contain ':' (URLs, nested coordinates) and stop at
+// whitespace. Receipt and retry ids are [A-Za-z0-9_-]+. Reasons are free
+// text up to the next '→' or end of line.
+//
+// Two spellings from the spec's examples parse to the canonical forms:
+//
+//   ◈ git:clone:invalid_url → ERROR
+//   repo_not_found
+//   ◈ RETRY:clone_xyz
+//
+// is `◈ ERROR:git:clone:invalid_url → repo_not_found → ◈ RETRY:clone_xyz`:
+// a bare `ERROR` makes the coordinate before it the failed operation, and
+// its reason may start on the next line. And
+//
+//   ◈ MEM:QUERY:research → count(receipts) == 3
+//
+// is `◈ MEM:QUERY:research|count(receipts)==3`: a query's aggregate (see
+// `query`) may follow `→`, with spaces around the comparison.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+pub const MARKER: char = '◈';
+pub const ARROW: char = '→';
+
+const RECEIPT_KEYWORD: &str = "RECEIPT:";
+const QUERY_KEYWORD: &str = "MEM:QUERY:";
+const ERROR_KEYWORD: &str = "ERROR:";
+const RETRY_KEYWORD: &str = "RETRY:";
+const BARE_ERROR: &str = "ERROR";
+
+/// Aggregates the spec writes after `→` instead of `|`
+const QUERY_AGGREGATES: [&str; 5] = ["count(receipts)", "count", "latest", "any_failed", "any-failed"];
+
+// ============================================================================
+// AST
+// ============================================================================
+
+/// Byte range into the parsed input
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        Span { start, end }
+    }
+
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}..{}", self.start, self.end)
+    }
+}
+
+/// `◈ a → ◈ b → ...`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chain {
+    pub exprs: Vec<Expr>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Coordinate(Coordinate),
+    Receipt(ReceiptRef),
+    Query(Query),
+    Error(ErrorReport),
+}
+
+/// Type 1: `subject:action:context`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Coordinate {
+    pub subject: String,
+    pub action: String,
+    pub context: Option<String>,
+    pub span: Span,
+}
+
+/// Type 2: `RECEIPT:id`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReceiptRef {
+    pub id: String,
+    pub span: Span,
+}
+
+/// Type 3: `MEM:QUERY:pattern`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    pub pattern: String,
+    pub span: Span,
+}
+
+/// Type 4: `ERROR:operation → reason → ◈ RETRY:id`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ErrorReport {
+    pub operation: String,
+    pub reason: String,
+    pub retry: Option<String>,
+    pub span: Span,
+}
+
+impl Expr {
+    pub fn span(&self) -> Span {
+        match self {
+            Expr::Coordinate(c) => c.span,
+            Expr::Receipt(r) => r.span,
+            Expr::Query(q) => q.span,
+            Expr::Error(e) => e.span,
+        }
+    }
+}
+
+impl Chain {
+    /// Receipt references anywhere in the chain
+    pub fn receipts(&self) -> impl Iterator<Item = &ReceiptRef> {
+        self.exprs.iter().filter_map(|e| match e {
+            Expr::Receipt(r) => Some(r),
+            _ => None,
+        })
+    }
+
+    pub fn first(&self) -> &Expr {
+        &self.exprs[0]
+    }
+}
+
+impl fmt::Display for Coordinate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.subject, self.action)?;
+        if let Some(context) = &self.context {
+            write!(f, ":{}", context)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expr::Coordinate(c) => write!(f, "{}", c),
+            Expr::Receipt(r) => write!(f, "{}{}", RECEIPT_KEYWORD, r.id),
+            Expr::Query(q) => write!(f, "{}{}", QUERY_KEYWORD, q.pattern),
+            Expr::Error(e) => {
+                write!(f, "{}{} {} {}", ERROR_KEYWORD, e.operation, ARROW, e.reason)?;
+                if let Some(retry) = &e.retry {
+                    write!(f, " {} {} {}{}", ARROW, MARKER, RETRY_KEYWORD, retry)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl fmt::Display for Chain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, expr) in self.exprs.iter().enumerate() {
+            if i > 0 {
+                write!(f, " {} ", ARROW)?;
+            }
+            write!(f, "{} {}", MARKER, expr)?;
+        }
+        Ok(())
+    }
+}
+
+// ============================================================================
+// ERRORS
+// ============================================================================
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    /// Input does not start with `◈`
+    MissingMarker,
+    /// A subject, action, id, pattern, operation or reason is empty
+    Empty(&'static str),
+    /// `subject` with no `:action`
+    MissingAction,
+    /// Receipt or retry id contains a character outside [A-Za-z0-9_-]
+    InvalidId(char),
+    /// `ERROR:op` not followed by `→ reason`
+    MissingErrorReason,
+    /// `→` with nothing after it
+    DanglingArrow,
+    /// `RETRY:` outside an error expression
+    UnexpectedRetry,
+    /// Text after a complete expression
+    TrailingInput,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub kind: ParseErrorKind,
+    pub span: Span,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            ParseErrorKind::MissingMarker => write!(f, "expected '{}'", MARKER)?,
+            ParseErrorKind::Empty(what) => write!(f, "empty {}", what)?,
+            ParseErrorKind::MissingAction => write!(f, "expected ':action' after subject")?,
+            ParseErrorKind::InvalidId(c) => write!(f, "invalid character '{}' in id", c)?,
+            ParseErrorKind::MissingErrorReason => write!(f, "expected '{} reason' after ERROR", ARROW)?,
+            ParseErrorKind::DanglingArrow => write!(f, "expected expression after '{}'", ARROW)?,
+            ParseErrorKind::UnexpectedRetry => write!(f, "RETRY is only valid inside an ERROR expression")?,
+            ParseErrorKind::TrailingInput => write!(f, "unexpected trailing input")?,
+        }
+        write!(f, " at {}", self.span)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+// ============================================================================
+// PARSER
+// ============================================================================
+
+/// Parse a complete A2AC(md) message (a single chain, nothing else)
+pub fn parse(input: &str) -> Result<Chain, ParseError> {
+    let mut p = Parser::new(input, 0);
+    p.skip_ws();
+    let chain = p.chain()?;
+    p.skip_ws();
+    if p.pos < input.len() {
+        return Err(p.error(ParseErrorKind::TrailingInput, p.pos, input.len()));
+    }
+    Ok(chain)
+}
+
+/// Parse a single expression without the leading `◈` (e.g. `git:clone:repo`)
+pub fn parse_expr(input: &str) -> Result<Expr, ParseError> {
+    let mut p = Parser::new(input, 0);
+    p.skip_ws();
+    let expr = p.expr()?;
+    p.skip_ws();
+    if p.pos < input.len() {
+        return Err(p.error(ParseErrorKind::TrailingInput, p.pos, input.len()));
+    }
+    Ok(expr)
+}
+
+/// Find every well-formed chain embedded in free text.
+///
+/// Chains start at a `◈` marker. A bare `RECEIPT:id` at a word boundary is
+/// also accepted, since agents often cite receipts inline in prose.
+pub fn scan(text: &str) -> Vec<Chain> {
+    let mut found = Vec::new();
+    let mut pos = 0;
+
+    while pos < text.len() {
+        let rest = &text[pos..];
+        let at_marker = rest.starts_with(MARKER);
+        let at_bare_receipt = rest.starts_with(RECEIPT_KEYWORD)
+            && text[..pos]
+                .chars()
+                .next_back()
+                .is_none_or(|c| !c.is_alphanumeric() && c != '_');
+
+        if at_marker || at_bare_receipt {
+            let mut p = Parser::new(text, pos);
+            let parsed = if at_marker {
+                p.chain()
+            } else {
+                p.expr().map(|expr| Chain {
+                    span: expr.span(),
+                    exprs: vec![expr],
+                })
+            };
+
+            if let Ok(chain) = parsed {
+                pos = chain.span.end.max(pos + 1);
+                found.push(chain);
+                continue;
+            }
+        }
+
+        pos += rest.chars().next().map_or(1, char::len_utf8);
+    }
+
+    found
+}
+
+struct Parser<'a> {
+    src: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(src: &'a str, pos: usize) -> Self {
+        Parser { src, pos }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.src[self.pos..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn error(&self, kind: ParseErrorKind, start: usize, end: usize) -> ParseError {
+        ParseError {
+            kind,
+            span: Span::new(start, end),
+        }
+    }
+
+    fn skip_ws(&mut self) {
+        let trimmed = self.rest().trim_start();
+        self.pos = self.src.len() - trimmed.len();
+    }
+
+    /// Skip spaces and tabs but not newlines
+    fn skip_inline_ws(&mut self) {
+        let trimmed = self.rest().trim_start_matches([' ', '\t']);
+        self.pos = self.src.len() - trimmed.len();
+    }
+
+    fn eat(&mut self, s: &str) -> bool {
+        if self.rest().starts_with(s) {
+            self.pos += s.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn eat_char(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += c.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn take_while(&mut self, f: impl Fn(char) -> bool) -> (usize, &'a str) {
+        let start = self.pos;
+        let len = self
+            .rest()
+            .char_indices()
+            .find(|&(_, c)| !f(c))
+            .map_or(self.rest().len(), |(i, _)| i);
+        self.pos += len;
+        (start, &self.src[start..self.pos])
+    }
+
+    fn chain(&mut self) -> Result<Chain, ParseError> {
+        let start = self.pos;
+        if !self.eat_char(MARKER) {
+            let end = start + self.peek().map_or(0, char::len_utf8);
+            return Err(self.error(ParseErrorKind::MissingMarker, start, end));
+        }
+        self.skip_inline_ws();
+
+        let mut exprs = vec![self.expr()?];
+        let mut end = self.pos;
+
+        loop {
+            let before = self.pos;
+            self.skip_ws();
+            if !self.eat_char(ARROW) {
+                self.pos = before;
+                break;
+            }
+            let arrow = self.pos - ARROW.len_utf8();
+            self.skip_ws();
+            self.eat_char(MARKER);
+            self.skip_inline_ws();
+            if self.rest().is_empty() {
+                return Err(self.error(ParseErrorKind::DanglingArrow, arrow, self.pos));
+            }
+            let expr = match exprs.last() {
+                Some(Expr::Coordinate(operation)) if self.at_bare_error() => {
+                    let (start, operation) = (operation.span.start, operation.to_string());
+                    exprs.pop();
+                    self.pos += BARE_ERROR.len();
+                    Expr::Error(self.error_reason(start, operation)?)
+                }
+                _ => self.expr()?,
+            };
+            exprs.push(expr);
+            end = self.pos;
+        }
+
+        Ok(Chain {
+            exprs,
+            span: Span::new(start, end),
+        })
+    }
+
+    fn expr(&mut self) -> Result<Expr, ParseError> {
+        let start = self.pos;
+
+        if self.eat(RECEIPT_KEYWORD) {
+            let id = self.id("receipt id")?;
+            return Ok(Expr::Receipt(ReceiptRef {
+                id,
+                span: Span::new(start, self.pos),
+            }));
+        }
+
+        if self.eat(QUERY_KEYWORD) {
+            let mut pattern = self.word("query pattern")?;
+            if !pattern.contains('|') {
+                if let Some(aggregate) = self.arrow_aggregate() {
+                    pattern = format!("{}|{}", pattern, aggregate);
+                }
+            }
+            if pattern.ends_with("|count") || pattern.ends_with("|count(receipts)") {
+                if let Some(comparison) = self.spaced_comparison() {
+                    pattern.push_str(&comparison);
+                }
+            }
+            return Ok(Expr::Query(Query {
+                pattern,
+                span: Span::new(start, self.pos),
+            }));
+        }
+
+        if self.eat(ERROR_KEYWORD) {
+            return self.error_report(start).map(Expr::Error);
+        }
+
+        if self.rest().starts_with(RETRY_KEYWORD) {
+            let end = start + RETRY_KEYWORD.len();
+            return Err(self.error(ParseErrorKind::UnexpectedRetry, start, end));
+        }
+
+        self.coordinate(start).map(Expr::Coordinate)
+    }
+
+    fn coordinate(&mut self, start: usize) -> Result<Coordinate, ParseError> {
+        let (_, subject) = self.take_while(is_segment_char);
+        if subject.is_empty() {
+            let end = start + self.peek().map_or(0, char::len_utf8);
+            return Err(self.error(ParseErrorKind::Empty("subject"), start, end));
+        }
+
+        if !self.eat_char(':') {
+            return Err(self.error(ParseErrorKind::MissingAction, start, self.pos));
+        }
+
+        let (action_start, action) = self.take_while(is_segment_char);
+        if action.is_empty() {
+            return Err(self.error(ParseErrorKind::Empty("action"), action_start, self.pos));
+        }
+
+        let context = if self.eat_char(':') {
+            Some(self.word("context")?)
+        } else {
+            None
+        };
+
+        Ok(Coordinate {
+            subject: subject.to_string(),
+            action: action.to_string(),
+            context,
+            span: Span::new(start, self.pos),
+        })
+    }
+
+    fn error_report(&mut self, start: usize) -> Result<ErrorReport, ParseError> {
+        let operation = self.word("error operation")?;
+
+        let before = self.pos;
+        self.skip_ws();
+        if !self.eat_char(ARROW) {
+            return Err(self.error(ParseErrorKind::MissingErrorReason, before, self.pos));
+        }
+        self.error_reason(start, operation)
+    }
+
+    /// Bare `ERROR` after `→`, not the `ERROR:` keyword
+    fn at_bare_error(&self) -> bool {
+        self.rest()
+            .strip_prefix(BARE_ERROR)
+            .is_some_and(|rest| rest.chars().next().is_none_or(char::is_whitespace))
+    }
+
+    /// Reason and optional retry of an error report for `operation`
+    fn error_reason(&mut self, start: usize, operation: String) -> Result<ErrorReport, ParseError> {
+        self.skip_ws();
+
+        let (reason_start, reason) = self.take_while(|c| c != ARROW && c != '\n' && c != MARKER);
+        let reason = reason.trim_end();
+        if reason.is_empty() {
+            return Err(self.error(ParseErrorKind::Empty("error reason"), reason_start, self.pos));
+        }
+        self.pos = reason_start + reason.len();
+        let mut end = self.pos;
+
+        // Optional `→ ◈ RETRY:id`, or `◈ RETRY:id` on the next line
+        let before_retry = self.pos;
+        self.skip_ws();
+        let arrow = self.eat_char(ARROW);
+        if arrow {
+            self.skip_ws();
+        }
+        let marker = self.eat_char(MARKER);
+        self.skip_inline_ws();
+        let mut retry = None;
+        if (arrow || marker) && self.eat(RETRY_KEYWORD) {
+            retry = Some(self.id("retry id")?);
+            end = self.pos;
+        }
+        if retry.is_none() {
+            self.pos = before_retry;
+        }
+
+        Ok(ErrorReport {
+            operation,
+            reason: reason.to_string(),
+            retry,
+            span: Span::new(start, end),
+        })
+    }
+
+    /// `→ count(receipts)` after a query pattern: an aggregate, not a
+    /// coordinate
+    fn arrow_aggregate(&mut self) -> Option<String> {
+        let before = self.pos;
+        self.skip_inline_ws();
+        if self.eat_char(ARROW) {
+            self.skip_inline_ws();
+            let (_, word) = self.take_while(is_segment_char);
+            if QUERY_AGGREGATES.contains(&word) && self.peek() != Some(':') {
+                return Some(word.to_string());
+            }
+        }
+        self.pos = before;
+        None
+    }
+
+    /// ` == 3` after a count aggregate, returned without the spaces
+    fn spaced_comparison(&mut self) -> Option<String> {
+        let before = self.pos;
+        self.skip_inline_ws();
+        let (_, symbol) = self.take_while(|c| matches!(c, '=' | '!' | '<' | '>'));
+        self.skip_inline_ws();
+        let (_, n) = self.take_while(|c| c.is_ascii_digit());
+        if !symbol.is_empty() && !n.is_empty() && self.peek().is_none_or(|c| !is_word_char(c)) {
+            return Some(format!("{}{}", symbol, n));
+        }
+        self.pos = before;
+        None
+    }
+
+    /// Receipt/retry id: [A-Za-z0-9_-]+
+    fn id(&mut self, what: &'static str) -> Result<String, ParseError> {
+        let (start, id) = self.take_while(is_id_char);
+        if id.is_empty() {
+            return match self.peek() {
+                Some(c) if is_word_char(c) => {
+                    Err(self.error(ParseErrorKind::InvalidId(c), start, start + c.len_utf8()))
+                }
+                _ => Err(self.error(ParseErrorKind::Empty(what), start, start)),
+            };
+        }
+        Ok(id.to_string())
+    }
+
+    /// Context-like token: anything up to whitespace, `→` or `◈`
+    fn word(&mut self, what: &'static str) -> Result<String, ParseError> {
+        let (start, word) = self.take_while(is_word_char);
+        if word.is_empty() {
+            return Err(self.error(ParseErrorKind::Empty(what), start, start));
+        }
+        Ok(word.to_string())
+    }
+}
+
+fn is_word_char(c: char) -> bool {
+    !c.is_whitespace() && c != ARROW && c != MARKER
+}
+
+fn is_segment_char(c: char) -> bool {
+    is_word_char(c) && c != ':'
+}
+
+fn is_id_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '-'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_coordinate() {
+        let chain = parse("◈ git:clone:github.com/user/repo").unwrap();
+        let Expr::Coordinate(c) = chain.first() else { panic!("expected coordinate") };
+        assert_eq!(c.subject, "git");
+        assert_eq!(c.action, "clone");
+        assert_eq!(c.context.as_deref(), Some("github.com/user/repo"));
+        assert_eq!(&"◈ git:clone:github.com/user/repo"[c.span.start..c.span.end], "git:clone:github.com/user/repo");
+
+        let chain = parse("◈ BRAIN:LIST").unwrap();
+        assert_eq!(chain.to_string(), "◈ BRAIN:LIST");
+    }
+
+    #[test]
+    fn test_parse_receipt_and_query_chain() {
+        let chain = parse("◈ git:clone:user/project → ◈ RECEIPT:abc123").unwrap();
+        assert_eq!(chain.exprs.len(), 2);
+        assert_eq!(chain.receipts().next().unwrap().id, "abc123");
+
+        let chain = parse("◈ MEM:QUERY:git → analyze:code").unwrap();
+        assert!(matches!(&chain.exprs[0], Expr::Query(q) if q.pattern == "git"));
+        assert!(matches!(&chain.exprs[1], Expr::Coordinate(c) if c.subject == "analyze"));
+    }
+
+    #[test]
+    fn test_parse_error_report() {
+        let chain = parse("◈ ERROR:git:clone:invalid_url → repo_not_found → ◈ RETRY:clone_xyz").unwrap();
+        let Expr::Error(e) = chain.first() else { panic!("expected error") };
+        assert_eq!(e.operation, "git:clone:invalid_url");
+        assert_eq!(e.reason, "repo_not_found");
+        assert_eq!(e.retry.as_deref(), Some("clone_xyz"));
+        assert_eq!(
+            chain.to_string(),
+            "◈ ERROR:git:clone:invalid_url → repo_not_found → ◈ RETRY:clone_xyz"
+        );
+
+        let chain = parse("◈ ERROR:deploy → timeout after 30s").unwrap();
+        assert!(matches!(chain.first(), Expr::Error(e) if e.reason == "timeout after 30s" && e.retry.is_none()));
+    }
+
+    #[test]
+    fn test_parse_spec_examples() {
+        // Type 4 example: bare ERROR, reason and retry on their own lines
+        let chain = parse("◈ git:clone:invalid_url → ERROR\nrepo_not_found\n◈ RETRY:clone_xyz").unwrap();
+        assert_eq!(chain.exprs.len(), 1);
+        assert_eq!(
+            chain.to_string(),
+            "◈ ERROR:git:clone:invalid_url → repo_not_found → ◈ RETRY:clone_xyz"
+        );
+        let chain = parse("◈ deploy:prod → ERROR disk full").unwrap();
+        assert!(matches!(chain.first(), Expr::Error(e) if e.operation == "deploy:prod" && e.reason == "disk full"));
+        assert_eq!(parse("◈ git:clone → ERROR").unwrap_err().kind, ParseErrorKind::Empty("error reason"));
+
+        // Pattern 2 completion check: aggregate after `→`, spaced comparison
+        let chain = parse("◈ MEM:QUERY:research → count(receipts) == 3").unwrap();
+        assert_eq!(chain.exprs.len(), 1);
+        assert!(matches!(chain.first(), Expr::Query(q) if q.pattern == "research|count(receipts)==3"));
+        let chain = parse("◈ MEM:QUERY:git:*|count >= 2 → deploy:prod").unwrap();
+        assert!(matches!(&chain.exprs[0], Expr::Query(q) if q.pattern == "git:*|count>=2"));
+        let chain = parse("◈ MEM:QUERY:git → latest").unwrap();
+        assert!(matches!(chain.first(), Expr::Query(q) if q.pattern == "git|latest"));
+
+        // `count:x` after a query is still a coordinate
+        let chain = parse("◈ MEM:QUERY:git → count:lines").unwrap();
+        assert_eq!(chain.exprs.len(), 2);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let kind = |s: &str| parse(s).unwrap_err().kind;
+
+        assert_eq!(kind("git:clone"), ParseErrorKind::MissingMarker);
+        assert_eq!(kind("◈ git"), ParseErrorKind::MissingAction);
+        assert_eq!(kind("◈ git:"), ParseErrorKind::Empty("action"));
+        assert_eq!(kind("◈ RECEIPT:"), ParseErrorKind::Empty("receipt id"));
+        assert_eq!(kind("◈ RECEIPT:!x"), ParseErrorKind::InvalidId('!'));
+        assert_eq!(kind("◈ ERROR:deploy"), ParseErrorKind::MissingErrorReason);
+        assert_eq!(kind("◈ git:clone →"), ParseErrorKind::DanglingArrow);
+        assert_eq!(kind("◈ RETRY:abc"), ParseErrorKind::UnexpectedRetry);
+        assert_eq!(kind("◈ git:clone and more"), ParseErrorKind::TrailingInput);
+
+        let err = parse("◈ git:clone and more").unwrap_err();
+        assert_eq!(err.span, Span::new(14, 22));
+    }
+
+    #[test]
+    fn test_scan_embedded() {
+        let text = "Clone finished. ◈ git:clone:repo → ◈ RECEIPT:clone_xyz, see also RECEIPT:AbC-1.";
+        let chains = scan(text);
+        assert_eq!(chains.len(), 2);
+        let ids: Vec<_> = chains.iter().flat_map(|c| c.receipts()).map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, ["clone_xyz", "AbC-1"]);
+
+        // Not at a word boundary
+        assert!(scan("NORECEIPT:abc").is_empty());
+    }
+}
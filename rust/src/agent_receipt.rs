@@ -0,0 +1,221 @@
+// agent_receipt.rs
+// The proto `AgentReceipt` (agent_voxel.proto) and its README signature:
+//
+//   signature = hex(SHA-256(receiptId + coordinate + success))[..16]
+//
+// The README does not say how `success` is spelled. Signing writes
+// "true"/"false" (the JSON spelling); verification also accepts "True"/"False",
+// which is what `str(True)` gives an agent signing in Python.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::time::{SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+
+use crate::hex_coordinate::HexCoordinate;
+use crate::short_hex;
+
+/// Every `AgentReceipt` id starts with this
+pub const RECEIPT_ID_PREFIX: &str = "RCPT-";
+
+/// Hex characters after the prefix
+pub const RECEIPT_ID_HEX_LENGTH: usize = 12;
+
+/// Hex characters of SHA-256 kept in the signature
+pub const SIGNATURE_LENGTH: usize = 16;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum AgentReceiptError {
+    #[error("receipt id '{0}' is not RCPT- followed by 12 hex characters")]
+    InvalidReceiptId(String),
+
+    #[error("signature mismatch for {receipt_id}: expected {expected}, got {actual}")]
+    SignatureMismatch {
+        receipt_id: String,
+        expected: String,
+        actual: String,
+    },
+}
+
+// ============================================================================
+// AGENT RECEIPT
+// ============================================================================
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentReceipt {
+    /// "RCPT-c8f1a2b7cc70"
+    pub receipt_id: String,
+    /// Hex coordinate of the completed action
+    pub coordinate: String,
+    pub success: bool,
+    /// ISO8601 UTC, e.g. "2026-01-15T06:11:19.539646Z"
+    pub timestamp: String,
+    /// Truncated SHA-256, see `sign`
+    pub signature: String,
+}
+
+impl AgentReceipt {
+    /// Signed receipt for `coordinate` with a fresh id, stamped now
+    pub fn new(coordinate: &HexCoordinate, success: bool) -> Self {
+        let coordinate = coordinate.to_string();
+        let receipt_id = generate_receipt_id(&coordinate);
+
+        AgentReceipt {
+            signature: sign(&receipt_id, &coordinate, success),
+            receipt_id,
+            coordinate,
+            success,
+            timestamp: iso8601_utc(SystemTime::now()),
+        }
+    }
+
+    /// Check the id format and the signature
+    pub fn verify(&self) -> Result<(), AgentReceiptError> {
+        if !is_receipt_id(&self.receipt_id) {
+            return Err(AgentReceiptError::InvalidReceiptId(self.receipt_id.clone()));
+        }
+        if !verify_signature(&self.receipt_id, &self.coordinate, self.success, &self.signature) {
+            return Err(AgentReceiptError::SignatureMismatch {
+                receipt_id: self.receipt_id.clone(),
+                expected: sign(&self.receipt_id, &self.coordinate, self.success),
+                actual: self.signature.clone(),
+            });
+        }
+        Ok(())
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.verify().is_ok()
+    }
+}
+
+// ============================================================================
+// SIGNATURES
+// ============================================================================
+
+/// README signature: first 16 hex chars of SHA-256(receiptId + coordinate + success)
+pub fn sign(receipt_id: &str, coordinate: &str, success: bool) -> String {
+    signature_over(receipt_id, coordinate, if success { "true" } else { "false" })
+}
+
+/// Check `signature` against either spelling of `success`
+pub fn verify_signature(receipt_id: &str, coordinate: &str, success: bool, signature: &str) -> bool {
+    let spellings = if success { ["true", "True"] } else { ["false", "False"] };
+    spellings
+        .iter()
+        .any(|s| signature_over(receipt_id, coordinate, s).eq_ignore_ascii_case(signature))
+}
+
+fn signature_over(receipt_id: &str, coordinate: &str, success: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(receipt_id.as_bytes());
+    hasher.update(coordinate.as_bytes());
+    hasher.update(success.as_bytes());
+
+    let digest = hasher.finalize();
+    let hex: String = digest.iter().map(|b| format!("{:02x}", b)).collect();
+    hex[..SIGNATURE_LENGTH].to_string()
+}
+
+/// Fresh "RCPT-" + 12 lowercase hex id
+pub fn generate_receipt_id(seed: &str) -> String {
+    format!("{}{}", RECEIPT_ID_PREFIX, short_hex(seed.as_bytes()))
+}
+
+/// "RCPT-" followed by exactly 12 hex characters
+pub fn is_receipt_id(id: &str) -> bool {
+    id.strip_prefix(RECEIPT_ID_PREFIX).is_some_and(|hex| {
+        hex.len() == RECEIPT_ID_HEX_LENGTH && hex.chars().all(|c| c.is_ascii_hexdigit())
+    })
+}
+
+// ============================================================================
+// TIMESTAMPS
+// ============================================================================
+
+/// "YYYY-MM-DDTHH:MM:SS.ffffffZ"
+fn iso8601_utc(time: SystemTime) -> String {
+    let since = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = since.as_secs();
+    let (year, month, day) = civil_from_days((secs / 86_400) as i64);
+    let rem = secs % 86_400;
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:06}Z",
+        year,
+        month,
+        day,
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60,
+        since.subsec_micros()
+    )
+}
+
+/// Days since 1970-01-01 to (year, month, day) in the proleptic Gregorian calendar
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_readme_signature() {
+        let coordinate = "0x600:04:CASE:RSLV:a1b2c3";
+        assert_eq!(sign("RCPT-c8f1a2b7cc70", coordinate, true), "1ccb281bfe9a9145");
+        assert_eq!(sign("RCPT-c8f1a2b7cc70", coordinate, false), "743c0411217a721b");
+
+        // Signed over "True", as `str(True)` in Python gives
+        assert!(verify_signature("RCPT-c8f1a2b7cc70", coordinate, true, "05fde4c7e4d0b721"));
+        assert!(!verify_signature("RCPT-c8f1a2b7cc70", coordinate, false, "1ccb281bfe9a9145"));
+
+        let json = r#"{
+            "receiptId": "RCPT-c8f1a2b7cc70",
+            "coordinate": "0x600:04:CASE:RSLV:a1b2c3",
+            "success": true,
+            "timestamp": "2026-01-15T06:11:19.539646Z",
+            "signature": "1ccb281bfe9a9145"
+        }"#;
+        let receipt: AgentReceipt = serde_json::from_str(json).unwrap();
+        assert!(receipt.is_valid());
+
+        let forged = AgentReceipt { success: false, ..receipt };
+        assert!(matches!(forged.verify(), Err(AgentReceiptError::SignatureMismatch { .. })));
+    }
+
+    #[test]
+    fn test_new_receipt() {
+        let coordinate: HexCoordinate = "0x600:04:CASE:RSLV:a1b2c3".parse().unwrap();
+        let receipt = AgentReceipt::new(&coordinate, true);
+
+        assert!(is_receipt_id(&receipt.receipt_id));
+        assert!(receipt.is_valid());
+        assert!(receipt.timestamp.ends_with('Z'));
+
+        assert!(!is_receipt_id("rcpt_c8f1a2b7cc70"));
+        assert!(!is_receipt_id("RCPT-c8f1a2b7cc7"));
+        let bad_id = AgentReceipt { receipt_id: "RCPT-xyz".to_string(), ..receipt };
+        assert!(matches!(bad_id.verify(), Err(AgentReceiptError::InvalidReceiptId(_))));
+    }
+
+    #[test]
+    fn test_iso8601() {
+        let time = UNIX_EPOCH + Duration::new(1_768_457_479, 539_646_000);
+        assert_eq!(iso8601_utc(time), "2026-01-15T06:11:19.539646Z");
+        assert_eq!(iso8601_utc(UNIX_EPOCH), "1970-01-01T00:00:00.000000Z");
+        assert_eq!(civil_from_days(11_016), (2000, 2, 29));
+    }
+}
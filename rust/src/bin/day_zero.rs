@@ -0,0 +1,478 @@
+// day_zero
+// Q Protocol Runtime Enforcer (command-line entry point)
+//
+// Usage:
+//   day_zero --agent <id> --trace <id> --brain <url> [options] [FILE]
+//
+// Reads outgoing messages from FILE (or stdin), one per line or as JSONL
+// objects with a "message" field, enforces each with DayZero, then prints
+// the compliance and graduation reports.
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::process::ExitCode;
+use std::sync::Arc;
+use std::time::Duration;
+
+use q_protocol::{
+    tokenizer, BpeTokenizer, CoordinateRegistry, DayZero, EnforcementReport, Error, HttpBackend, Keyring, MessageReport,
+    Policy, ViolationSeverity,
+};
+
+// ============================================================================
+// EXIT CODES
+// ============================================================================
+
+const EXIT_OK: u8 = 0; // No violations at or above --fail-on
+const EXIT_VIOLATIONS: u8 = 1; // Violations at or above --fail-on
+const EXIT_CRITICAL: u8 = 2; // Critical violation or blocked message
+const EXIT_NOT_GRADUATED: u8 = 3; // --require-graduation and not graduated
+const EXIT_USAGE: u8 = 64; // Bad command line
+const EXIT_DATA: u8 = 65; // Malformed input line
+const EXIT_NOINPUT: u8 = 66; // Input file unreadable
+
+const USAGE: &str = "\
+◈ day_zero - Q Protocol Runtime Enforcer
+
+Usage: day_zero --agent <id> --trace <id> --brain <url> [options] [FILE]
+
+Reads outgoing messages from FILE (default: stdin, or '-').
+
+Options:
+  --agent <id>            Agent identifier (required)
+  --trace <id>            Trace identifier (required)
+  --brain <url>           Brain base URL (required)
+  --brain-timeout <ms>    Give up on a brain request after this long (default: 10000)
+  --format <fmt>          auto | lines | jsonl (default: auto)
+  --output <fmt>          text | json | sarif (default: text)
+  --registry <file>       JSON subject/action ⇄ hex code registry
+  --policy <file>         TOML enforcement policy (default: built-in)
+  --keyring <file>        TOML agent keyring; check receipt signatures
+  --profile <name>        Policy profile, e.g. training | strict | production
+  --model <name>          Count tokens for this model, e.g. gpt-4o | gpt-4
+  --vocab-dir <dir>       Directory of <encoding>.tiktoken rank files (default: .)
+  --strict                Block messages with critical violations
+  --fail-on <severity>    info | warning | error | critical (default: error)
+  --require-graduation    Exit 3 unless the agent graduates
+  -h, --help              Show this help
+
+Exit codes:
+  0   clean
+  1   violations at or above --fail-on
+  2   critical violation or blocked message
+  3   graduation required but not achieved
+  64  usage error
+  65  malformed input or policy
+  66  input or policy not readable";
+
+// ============================================================================
+// ARGUMENTS
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum InputFormat {
+    Auto,
+    Lines,
+    Jsonl,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum OutputFormat {
+    Text,
+    Json,
+    Sarif,
+}
+
+#[derive(Debug)]
+struct Args {
+    agent: String,
+    trace: String,
+    brain: String,
+    brain_timeout: Option<Duration>,
+    input: Option<String>,
+    format: InputFormat,
+    output: OutputFormat,
+    registry: Option<String>,
+    policy: Option<String>,
+    keyring: Option<String>,
+    profile: Option<String>,
+    model: Option<String>,
+    vocab_dir: String,
+    strict: bool,
+    fail_on: ViolationSeverity,
+    require_graduation: bool,
+}
+
+enum Command {
+    Run(Box<Args>),
+    Help,
+}
+
+fn parse_args(mut argv: impl Iterator<Item = String>) -> Result<Command, String> {
+    let mut agent = None;
+    let mut trace = None;
+    let mut brain = None;
+    let mut brain_timeout = None;
+    let mut input = None;
+    let mut format = InputFormat::Auto;
+    let mut output = OutputFormat::Text;
+    let mut registry = None;
+    let mut policy = None;
+    let mut keyring = None;
+    let mut profile = None;
+    let mut model = None;
+    let mut vocab_dir = ".".to_string();
+    let mut strict = false;
+    let mut fail_on = ViolationSeverity::Error;
+    let mut require_graduation = false;
+
+    while let Some(arg) = argv.next() {
+        let mut value = |flag: &str| {
+            argv.next()
+                .ok_or_else(|| format!("missing value for {}", flag))
+        };
+
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "--agent" => agent = Some(value("--agent")?),
+            "--trace" => trace = Some(value("--trace")?),
+            "--brain" => brain = Some(value("--brain")?),
+            "--brain-timeout" => {
+                let ms = value("--brain-timeout")?;
+                let ms: u64 = ms.parse().map_err(|_| format!("invalid --brain-timeout: {}", ms))?;
+                brain_timeout = Some(Duration::from_millis(ms));
+            }
+            "--format" => {
+                format = match value("--format")?.as_str() {
+                    "auto" => InputFormat::Auto,
+                    "lines" => InputFormat::Lines,
+                    "jsonl" => InputFormat::Jsonl,
+                    other => return Err(format!("unknown format: {}", other)),
+                }
+            }
+            "--output" => {
+                output = match value("--output")?.as_str() {
+                    "text" => OutputFormat::Text,
+                    "json" => OutputFormat::Json,
+                    "sarif" => OutputFormat::Sarif,
+                    other => return Err(format!("unknown output format: {}", other)),
+                }
+            }
+            "--registry" => registry = Some(value("--registry")?),
+            "--policy" => policy = Some(value("--policy")?),
+            "--keyring" => keyring = Some(value("--keyring")?),
+            "--profile" => profile = Some(value("--profile")?),
+            "--model" => model = Some(value("--model")?),
+            "--vocab-dir" => vocab_dir = value("--vocab-dir")?,
+            "--strict" => strict = true,
+            "--fail-on" => fail_on = value("--fail-on")?.parse()?,
+            "--require-graduation" => require_graduation = true,
+            flag if flag.starts_with("--") => return Err(format!("unknown option: {}", flag)),
+            path => {
+                if input.replace(path.to_string()).is_some() {
+                    return Err("only one input file may be given".to_string());
+                }
+            }
+        }
+    }
+
+    Ok(Command::Run(Box::new(Args {
+        agent: agent.ok_or("--agent is required")?,
+        trace: trace.ok_or("--trace is required")?,
+        brain: brain.ok_or("--brain is required")?,
+        brain_timeout,
+        input,
+        format,
+        output,
+        registry,
+        policy,
+        keyring,
+        profile,
+        model,
+        vocab_dir,
+        strict,
+        fail_on,
+        require_graduation,
+    })))
+}
+
+// ============================================================================
+// INPUT
+// ============================================================================
+
+/// Decode one input line into a message; `None` for blank lines
+fn decode_line(line: &str, format: InputFormat) -> Result<Option<String>, String> {
+    let trimmed = line.trim();
+    if trimmed.is_empty() {
+        return Ok(None);
+    }
+
+    let is_json = match format {
+        InputFormat::Lines => false,
+        InputFormat::Jsonl => true,
+        InputFormat::Auto => trimmed.starts_with('{') || trimmed.starts_with('"'),
+    };
+
+    if !is_json {
+        return Ok(Some(trimmed.to_string()));
+    }
+
+    let value: serde_json::Value =
+        serde_json::from_str(trimmed).map_err(|e| format!("invalid JSON: {}", e))?;
+
+    match value {
+        serde_json::Value::String(message) => Ok(Some(message)),
+        serde_json::Value::Object(map) => match map.get("message") {
+            Some(serde_json::Value::String(message)) => Ok(Some(message.clone())),
+            _ => Err("JSON object has no string \"message\" field".to_string()),
+        },
+        _ => Err("expected a JSON object or string".to_string()),
+    }
+}
+
+/// Messages with their 1-based input line numbers
+fn read_messages(args: &Args) -> Result<Vec<(usize, String)>, (u8, String)> {
+    let reader: Box<dyn BufRead> = match args.input.as_deref() {
+        None | Some("-") => Box::new(BufReader::new(io::stdin())),
+        Some(path) => {
+            let file = File::open(path)
+                .map_err(|e| (EXIT_NOINPUT, format!("cannot open {}: {}", path, e)))?;
+            Box::new(BufReader::new(file))
+        }
+    };
+
+    let mut messages = Vec::new();
+    for (i, line) in reader.lines().enumerate() {
+        let line = line.map_err(|e| (EXIT_NOINPUT, format!("read error: {}", e)))?;
+        match decode_line(&line, args.format) {
+            Ok(Some(message)) => messages.push((i + 1, message)),
+            Ok(None) => {}
+            Err(e) => return Err((EXIT_DATA, format!("line {}: {}", i + 1, e))),
+        }
+    }
+
+    Ok(messages)
+}
+
+// ============================================================================
+// MAIN
+// ============================================================================
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> ExitCode {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(Command::Run(args)) => args,
+        Ok(Command::Help) => {
+            println!("{}", USAGE);
+            return ExitCode::from(EXIT_OK);
+        }
+        Err(e) => {
+            eprintln!("day_zero: {}\n\n{}", e, USAGE);
+            return ExitCode::from(EXIT_USAGE);
+        }
+    };
+
+    let messages = match read_messages(&args) {
+        Ok(messages) => messages,
+        Err((code, e)) => {
+            eprintln!("day_zero: {}", e);
+            return ExitCode::from(code);
+        }
+    };
+
+    let backend = HttpBackend::new(&args.brain).and_then(|backend| match args.brain_timeout {
+        Some(timeout) => backend.with_timeout(timeout),
+        None => Ok(backend),
+    });
+    let backend = match backend {
+        Ok(backend) => backend,
+        Err(e) => {
+            eprintln!("day_zero: {}", e);
+            return ExitCode::from(EXIT_USAGE);
+        }
+    };
+
+    let mut dz = DayZero::new(args.agent.clone(), args.trace.clone(), args.brain.clone())
+        .with_backend(Arc::new(backend));
+
+    if args.policy.is_some() || args.profile.is_some() {
+        let policy = match &args.policy {
+            Some(path) => match Policy::load(path) {
+                Ok(policy) => policy,
+                Err(e) => {
+                    eprintln!("day_zero: policy {}: {}", path, e);
+                    // Unreadable file vs. bad TOML or unknown rule ids
+                    let code = if matches!(e, Error::Io(_)) { EXIT_NOINPUT } else { EXIT_DATA };
+                    return ExitCode::from(code);
+                }
+            },
+            None => Policy::builtin(),
+        };
+        match policy.resolve(args.profile.as_deref()) {
+            Ok(settings) => dz = dz.with_policy(&settings),
+            Err(e) => {
+                eprintln!("day_zero: {}", e);
+                return ExitCode::from(EXIT_USAGE);
+            }
+        }
+    }
+
+    if let Some(model) = &args.model {
+        let tokenizer = match BpeTokenizer::for_model(model, &args.vocab_dir) {
+            Ok(bpe) => Box::new(bpe) as Box<dyn tokenizer::Tokenizer>,
+            Err(e) => {
+                eprintln!("day_zero: {}; falling back to heuristic token counts", e);
+                Box::new(tokenizer::HeuristicTokenizer)
+            }
+        };
+        dz = dz.with_tokenizer(tokenizer);
+    }
+
+    // --strict wins over the policy
+    if args.strict {
+        dz = dz.with_strict_mode(true);
+    }
+
+    if let Some(path) = &args.registry {
+        let registry = match CoordinateRegistry::load(path) {
+            Ok(registry) => registry,
+            Err(e) => {
+                eprintln!("day_zero: registry {}: {}", path, e);
+                return ExitCode::from(EXIT_NOINPUT);
+            }
+        };
+        dz = match dz.with_registry(&registry) {
+            Ok(dz) => dz,
+            Err(e) => {
+                eprintln!("day_zero: registry {}: {}", path, e);
+                return ExitCode::from(EXIT_DATA);
+            }
+        };
+    }
+
+    if let Some(path) = &args.keyring {
+        match Keyring::load(path) {
+            Ok(keyring) => dz = dz.with_keyring(Arc::new(keyring)),
+            Err(e) => {
+                eprintln!("day_zero: keyring {}: {}", path, e);
+                return ExitCode::from(EXIT_NOINPUT);
+            }
+        }
+    }
+
+    let mut worst: Option<ViolationSeverity> = None;
+    let mut blocked = 0;
+    let mut reports = Vec::new();
+
+    for (line, message) in &messages {
+        let evaluation = dz.evaluate_outgoing(message).await;
+        worst = worst.max(evaluation.max_severity());
+        if evaluation.is_blocked() {
+            blocked += 1;
+        }
+
+        if args.output != OutputFormat::Text {
+            reports.push(MessageReport::new(*line, message, &evaluation));
+            continue;
+        }
+
+        match evaluation.optimized {
+            Some(optimized) => {
+                for v in &evaluation.violations {
+                    eprintln!("⚠️  {}", v);
+                }
+                println!("{}", optimized);
+            }
+            None => {
+                eprintln!("❌ BLOCKED: {}", message);
+                for v in &evaluation.violations {
+                    eprintln!("   {}", v);
+                }
+            }
+        }
+    }
+
+    match args.output {
+        OutputFormat::Text => {
+            dz.print_report();
+            println!("{}", dz.graduation_report());
+        }
+        OutputFormat::Json | OutputFormat::Sarif => {
+            let report = EnforcementReport::new(&dz, reports);
+            let value = if args.output == OutputFormat::Json {
+                report.to_json()
+            } else {
+                report.to_sarif(args.input.as_deref().filter(|p| *p != "-").unwrap_or("stdin"))
+            };
+            match serde_json::to_string_pretty(&value) {
+                Ok(out) => println!("{}", out),
+                Err(e) => {
+                    eprintln!("day_zero: {}", e);
+                    return ExitCode::from(EXIT_DATA);
+                }
+            }
+        }
+    }
+
+    let code = if blocked > 0 || worst == Some(ViolationSeverity::Critical) {
+        EXIT_CRITICAL
+    } else if worst.is_some_and(|s| s >= args.fail_on) {
+        EXIT_VIOLATIONS
+    } else if args.require_graduation && !dz.check_graduation() {
+        EXIT_NOT_GRADUATED
+    } else {
+        EXIT_OK
+    };
+
+    ExitCode::from(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn argv(args: &[&str]) -> impl Iterator<Item = String> {
+        args.iter().map(|s| s.to_string()).collect::<Vec<_>>().into_iter()
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let cmd = parse_args(argv(&[
+            "--agent", "git-agent-001", "--trace", "trace_abc", "--brain", "http://brain",
+            "--brain-timeout", "2500", "--strict", "--fail-on", "critical", "--profile", "training", "--output", "sarif",
+            "msgs.jsonl",
+        ]))
+        .unwrap();
+
+        let Command::Run(args) = cmd else { panic!("expected run") };
+        assert_eq!(args.agent, "git-agent-001");
+        assert_eq!(args.brain_timeout, Some(Duration::from_millis(2500)));
+        assert!(args.strict);
+        assert_eq!(args.fail_on, ViolationSeverity::Critical);
+        assert_eq!(args.profile.as_deref(), Some("training"));
+        assert_eq!(args.policy, None);
+        assert_eq!(args.output, OutputFormat::Sarif);
+        assert_eq!(args.input.as_deref(), Some("msgs.jsonl"));
+
+        assert!(parse_args(argv(&["--agent", "a", "--trace", "t"])).is_err());
+        assert!(parse_args(argv(&["--bogus"])).is_err());
+    }
+
+    #[test]
+    fn test_decode_line() {
+        assert_eq!(decode_line("  ", InputFormat::Auto), Ok(None));
+        assert_eq!(
+            decode_line("◈ git:clone:repo", InputFormat::Auto),
+            Ok(Some("◈ git:clone:repo".to_string()))
+        );
+        assert_eq!(
+            decode_line(r#"{"message": "◈ RECEIPT:abc123"}"#, InputFormat::Auto),
+            Ok(Some("◈ RECEIPT:abc123".to_string()))
+        );
+        assert!(decode_line(r#"{"text": "x"}"#, InputFormat::Jsonl).is_err());
+        assert_eq!(
+            decode_line(r#"{"message": "x"}"#, InputFormat::Lines),
+            Ok(Some(r#"{"message": "x"}"#.to_string()))
+        );
+    }
+}
@@ -0,0 +1,296 @@
+// q-brain
+// Local Q Brain server: receipts, coordinates and queries over HTTP
+//
+// Usage:
+//   q-brain [--data <dir>] [--listen <addr>]
+//
+// Endpoints (JSON in and out; errors are {"error": "..."}):
+//
+//   GET  /trace/{trace_id}      receipts in the trace, oldest first ([] if none)
+//   GET  /receipt/{receipt_id}  one receipt, 404 if never stored
+//   POST /receipt               store a receipt; 409 duplicate, 422 bad hash or
+//                               link (the `ChainBreak` is in "reason")
+//   POST /coordinate            record {agent_id, trace_id, coordinate}
+//   GET  /query?pattern=<q>     receipts matching a MEM:QUERY pattern and its
+//                               filters (aggregates are left to the caller)
+//
+// Everything is kept in append-only JSONL files under --data.
+
+use std::net::SocketAddr;
+use std::process::ExitCode;
+use std::sync::Arc;
+
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::Deserialize;
+
+use q_protocol::{BrainBackend, BrainError, CoordinateRecord, Error, FileBackend, MemQuery, Receipt};
+
+const EXIT_USAGE: u8 = 64; // Bad command line
+const EXIT_NOINPUT: u8 = 66; // Data directory unreadable
+const EXIT_UNAVAILABLE: u8 = 69; // Cannot listen
+
+const DEFAULT_DATA_DIR: &str = "brain";
+const DEFAULT_LISTEN: &str = "127.0.0.1:8600";
+
+const USAGE: &str = "\
+◈ q-brain - Local Q Brain server
+
+Usage: q-brain [--data <dir>] [--listen <addr>]
+
+Options:
+  --data <dir>       Where receipts and coordinates are kept (default: ./brain)
+  --listen <addr>    Address to serve on (default: 127.0.0.1:8600)
+  -h, --help         Show this help";
+
+// ============================================================================
+// ARGUMENTS
+// ============================================================================
+
+#[derive(Debug)]
+struct Args {
+    data: String,
+    listen: SocketAddr,
+}
+
+enum Command {
+    Run(Args),
+    Help,
+}
+
+fn parse_args(mut argv: impl Iterator<Item = String>) -> Result<Command, String> {
+    let mut data = DEFAULT_DATA_DIR.to_string();
+    let mut listen = DEFAULT_LISTEN.to_string();
+
+    while let Some(arg) = argv.next() {
+        let mut value = |flag: &str| {
+            argv.next()
+                .ok_or_else(|| format!("missing value for {}", flag))
+        };
+
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "--data" => data = value("--data")?,
+            "--listen" => listen = value("--listen")?,
+            other => return Err(format!("unknown argument: {}", other)),
+        }
+    }
+
+    let listen = listen
+        .parse()
+        .map_err(|_| format!("invalid --listen address: {}", listen))?;
+    Ok(Command::Run(Args { data, listen }))
+}
+
+// ============================================================================
+// ROUTES
+// ============================================================================
+
+type Brain = Arc<FileBackend>;
+
+/// A failed request, rendered as `{"error": "..."}`, plus the receipt id
+/// and `ChainBreak` for rejected receipts
+struct ApiError(StatusCode, serde_json::Value);
+
+impl ApiError {
+    fn new(status: StatusCode, message: String) -> Self {
+        ApiError(status, serde_json::json!({ "error": message }))
+    }
+}
+
+impl From<Error> for ApiError {
+    fn from(e: Error) -> Self {
+        let status = match &e {
+            Error::Brain(BrainError::Duplicate(_)) => StatusCode::CONFLICT,
+            Error::Brain(BrainError::Rejected { .. }) => StatusCode::UNPROCESSABLE_ENTITY,
+            Error::Brain(BrainError::InvalidCoordinate(_)) | Error::Query(_) => StatusCode::BAD_REQUEST,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        let mut error = ApiError::new(status, e.to_string());
+        match &e {
+            Error::Brain(BrainError::Duplicate(receipt_id)) => error.1["receipt_id"] = receipt_id.as_str().into(),
+            Error::Brain(BrainError::Rejected { receipt_id, reason }) => {
+                error.1["receipt_id"] = receipt_id.as_str().into();
+                error.1["reason"] = serde_json::to_value(reason).unwrap_or_default();
+            }
+            _ => {}
+        }
+        error
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(self.1)).into_response()
+    }
+}
+
+fn router(brain: Brain) -> Router {
+    Router::new()
+        .route("/trace/{trace_id}", get(fetch_trace))
+        .route("/receipt/{receipt_id}", get(fetch_receipt))
+        .route("/receipt", post(store_receipt))
+        .route("/coordinate", post(record_coordinate))
+        .route("/query", get(query))
+        .with_state(brain)
+}
+
+async fn fetch_trace(State(brain): State<Brain>, Path(trace_id): Path<String>) -> Result<Json<Vec<Receipt>>, ApiError> {
+    Ok(Json(brain.fetch_trace(&trace_id).await?))
+}
+
+async fn fetch_receipt(State(brain): State<Brain>, Path(receipt_id): Path<String>) -> Result<Json<Receipt>, ApiError> {
+    match brain.fetch_receipt(&receipt_id).await? {
+        Some(receipt) => Ok(Json(receipt)),
+        None => Err(ApiError::new(StatusCode::NOT_FOUND, format!("receipt not found: {}", receipt_id))),
+    }
+}
+
+async fn store_receipt(State(brain): State<Brain>, Json(receipt): Json<Receipt>) -> Result<impl IntoResponse, ApiError> {
+    brain.store(receipt.clone()).await?;
+    Ok((StatusCode::CREATED, Json(receipt)))
+}
+
+async fn record_coordinate(
+    State(brain): State<Brain>,
+    Json(record): Json<CoordinateRecord>,
+) -> Result<impl IntoResponse, ApiError> {
+    let record = brain.record_coordinate(record).await?;
+    Ok((StatusCode::CREATED, Json(record)))
+}
+
+#[derive(Debug, Deserialize)]
+struct QueryParams {
+    pattern: String,
+}
+
+async fn query(State(brain): State<Brain>, Query(params): Query<QueryParams>) -> Result<Json<Vec<Receipt>>, ApiError> {
+    let query: MemQuery = params.pattern.parse().map_err(Error::from)?;
+    Ok(Json(brain.query(&query).await?))
+}
+
+// ============================================================================
+// MAIN
+// ============================================================================
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> ExitCode {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(Command::Run(args)) => args,
+        Ok(Command::Help) => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Err(e) => {
+            eprintln!("q-brain: {}\n\n{}", e, USAGE);
+            return ExitCode::from(EXIT_USAGE);
+        }
+    };
+
+    let brain = match FileBackend::open(&args.data) {
+        Ok(brain) => Arc::new(brain),
+        Err(e) => {
+            eprintln!("q-brain: {}: {}", args.data, e);
+            return ExitCode::from(EXIT_NOINPUT);
+        }
+    };
+
+    let listener = match tokio::net::TcpListener::bind(args.listen).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("q-brain: cannot listen on {}: {}", args.listen, e);
+            return ExitCode::from(EXIT_UNAVAILABLE);
+        }
+    };
+
+    println!("◈ q-brain listening on http://{} ({} receipts in {})", args.listen, brain.len(), args.data);
+    if let Err(e) = axum::serve(listener, router(brain)).await {
+        eprintln!("q-brain: {}", e);
+        return ExitCode::from(EXIT_UNAVAILABLE);
+    }
+    ExitCode::SUCCESS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use q_protocol::{DayZero, HttpBackend, ReceiptChain};
+
+    #[tokio::test]
+    async fn test_day_zero_against_brain() {
+        let dir = std::env::temp_dir().join(format!("q-brain-e2e-{}", std::process::id()));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let brain = Arc::new(FileBackend::open(&dir).unwrap());
+        tokio::spawn(async move { axum::serve(listener, router(brain)).await });
+
+        // An agent records its work...
+        let remote = Arc::new(HttpBackend::new(&base).unwrap());
+        let mut log = ReceiptChain::new("trace_abc");
+        log.append(Receipt::success("git:clone:repo", "git-agent-001", "trace_abc", "cloned"));
+        log.append(Receipt::success("test:run:repo", "git-agent-001", "trace_abc", "42 passed"));
+        for receipt in log.receipts() {
+            remote.store(receipt.clone()).await.unwrap();
+        }
+        assert!(matches!(
+            remote.store(log.receipts()[1].clone()).await,
+            Err(Error::Brain(BrainError::Duplicate(id))) if id == log.receipts()[1].receipt_id
+        ));
+        let unlinked = Receipt::success("git:tag:repo", "git-agent-001", "trace_abc", "tagged");
+        assert!(matches!(
+            remote.store(unlinked).await,
+            Err(Error::Brain(BrainError::Rejected { reason: q_protocol::ChainBreak::BrokenLink { .. }, .. }))
+        ));
+
+        let client = reqwest::Client::new();
+        let coordinate = client
+            .post(format!("{}/coordinate", base))
+            .json(&serde_json::json!({
+                "agent_id": "git-agent-001",
+                "trace_id": "trace_abc",
+                "coordinate": "◈ git:clone:repo",
+            }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(coordinate.status(), StatusCode::CREATED);
+        let queried: Vec<Receipt> = client
+            .get(format!("{}/query?pattern=test:*%5Bsuccess%3Dtrue%5D", base))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(queried, &log.receipts()[1..]);
+        let unknown = client.get(format!("{}/trace/trace_none", base)).send().await.unwrap();
+        assert_eq!(unknown.status(), StatusCode::OK);
+        assert_eq!(unknown.text().await.unwrap(), "[]");
+
+        // ...and DayZero, on another "machine", bootstraps from it and checks citations
+        let mut dz = DayZero::new("git-agent-001".to_string(), "trace_abc".to_string(), base.clone());
+        let cited = format!("Tests done ◈ RECEIPT:{}", log.receipts()[1].receipt_id);
+        assert!(dz.process_outgoing(&cited).await.is_ok());
+        assert!(dz.check_prior_work("git:clone:repo").is_some());
+        assert!(dz.enforce_receipts("Deploy done ◈ RECEIPT:rcpt_made_up").await.is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let argv = |args: &[&str]| args.iter().map(|s| s.to_string()).collect::<Vec<_>>().into_iter();
+
+        let Command::Run(args) = parse_args(argv(&["--data", "/tmp/brain", "--listen", "0.0.0.0:9000"])).unwrap() else {
+            panic!("expected run")
+        };
+        assert_eq!(args.data, "/tmp/brain");
+        assert_eq!(args.listen.port(), 9000);
+
+        assert!(parse_args(argv(&["--listen", "nowhere"])).is_err());
+        assert!(matches!(parse_args(argv(&["-h"])), Ok(Command::Help)));
+    }
+}
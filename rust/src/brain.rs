@@ -0,0 +1,646 @@
+// brain.rs
+// Q Brain backends: where receipts are stored and fetched for verification
+//
+// HTTP endpoints, relative to the brain base URL:
+//
+//   GET  /trace/{trace_id}     → [Receipt, …] oldest first ([] if none)
+//   GET  /receipt/{receipt_id} → Receipt (404: never seen)
+//   POST /receipt              ← Receipt (409: already stored; 422: rejected,
+//                                 with the `ChainBreak` as `reason`)
+//   GET  /query?pattern={q}    → [Receipt, …] matching a MEM:QUERY (see `query`)
+//
+// `FileBackend` keeps receipts and coordinates in append-only JSONL files
+// and is what the `q-brain` server serves from.
+
+use async_trait::async_trait;
+use reqwest::{StatusCode, Url};
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use std::time::Duration;
+use thiserror::Error;
+
+use crate::chain::ChainBreak;
+use crate::error::Error;
+use crate::query::MemQuery;
+use crate::receipt::Receipt;
+use crate::rules;
+
+/// Default per-request timeout for `HttpBackend`
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Why a brain request failed
+#[derive(Debug, Error)]
+pub enum BrainError {
+    #[error("invalid brain url '{url}': {reason}")]
+    InvalidUrl { url: String, reason: String },
+
+    #[error("{url} timed out after {}ms", timeout.as_millis())]
+    Timeout { url: String, timeout: Duration },
+
+    #[error("cannot reach {url}: {reason}")]
+    Connect { url: String, reason: String },
+
+    #[error("{url} returned HTTP {status}")]
+    Status { url: String, status: u16 },
+
+    #[error("{url} returned malformed receipts: {reason}")]
+    Decode { url: String, reason: String },
+
+    #[error("request to {url} failed: {reason}")]
+    Request { url: String, reason: String },
+
+    #[error("receipt {0} is already stored")]
+    Duplicate(String),
+
+    #[error("receipt {receipt_id} rejected: {reason}")]
+    Rejected { receipt_id: String, reason: ChainBreak },
+
+    #[error("'{0}' is not an A2AC(md) or hex coordinate")]
+    InvalidCoordinate(String),
+
+    #[error("{path}:{line}: {reason}")]
+    Corrupt { path: PathBuf, line: usize, reason: String },
+
+    #[error("backend lock poisoned")]
+    Poisoned,
+}
+
+/// Receipt storage the enforcer queries on bootstrap and verifies against
+#[async_trait]
+pub trait BrainBackend: Send + Sync {
+    /// Every receipt recorded under `trace_id`, oldest first
+    async fn fetch_trace(&self, trace_id: &str) -> Result<Vec<Receipt>, Error>;
+
+    /// The receipt with `receipt_id`, or `None` if the brain has never seen it
+    async fn fetch_receipt(&self, receipt_id: &str) -> Result<Option<Receipt>, Error>;
+
+    /// Record a receipt
+    async fn store(&self, receipt: Receipt) -> Result<(), Error>;
+
+    /// Receipts matching `query`'s pattern and filters, oldest first; the
+    /// caller applies any aggregate with `MemQuery::evaluate`
+    async fn query(&self, query: &MemQuery) -> Result<Vec<Receipt>, Error>;
+}
+
+// ============================================================================
+// IN-MEMORY BACKEND
+// ============================================================================
+
+/// Process-local brain, for tests and embedding
+#[derive(Debug, Default)]
+pub struct MemoryBackend {
+    receipts: RwLock<Vec<Receipt>>,
+}
+
+impl MemoryBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Backend pre-loaded with `receipts`, stored as given
+    pub fn with_receipts(receipts: impl IntoIterator<Item = Receipt>) -> Self {
+        MemoryBackend {
+            receipts: RwLock::new(receipts.into_iter().collect()),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.receipts.read().map(|r| r.len()).unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+fn poisoned<T>(_: T) -> Error {
+    BrainError::Poisoned.into()
+}
+
+#[async_trait]
+impl BrainBackend for MemoryBackend {
+    async fn fetch_trace(&self, trace_id: &str) -> Result<Vec<Receipt>, Error> {
+        let receipts = self.receipts.read().map_err(poisoned)?;
+        Ok(receipts.iter().filter(|r| r.trace_id == trace_id).cloned().collect())
+    }
+
+    async fn fetch_receipt(&self, receipt_id: &str) -> Result<Option<Receipt>, Error> {
+        let receipts = self.receipts.read().map_err(poisoned)?;
+        Ok(receipts.iter().find(|r| r.receipt_id == receipt_id).cloned())
+    }
+
+    async fn store(&self, receipt: Receipt) -> Result<(), Error> {
+        self.receipts.write().map_err(poisoned)?.push(receipt);
+        Ok(())
+    }
+
+    async fn query(&self, query: &MemQuery) -> Result<Vec<Receipt>, Error> {
+        let receipts = self.receipts.read().map_err(poisoned)?;
+        Ok(query.select(receipts.iter()).into_iter().cloned().collect())
+    }
+}
+
+// ============================================================================
+// FILE BACKEND
+// ============================================================================
+
+const RECEIPTS_FILE: &str = "receipts.jsonl";
+const COORDINATES_FILE: &str = "coordinates.jsonl";
+
+/// A coordinate an agent emitted to the brain
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CoordinateRecord {
+    pub agent_id: String,
+    pub trace_id: String,
+    /// `◈ subject:action:context`, `subject:action` or `0x600:…`
+    pub coordinate: String,
+    #[serde(default)]
+    pub timestamp: u64,
+}
+
+/// Brain persisted under a directory as append-only JSONL logs
+///
+/// Receipts are only accepted if their content hash verifies and they link
+/// to the current head of their trace, so the log on disk is always a set of
+/// intact chains.
+#[derive(Debug)]
+pub struct FileBackend {
+    dir: PathBuf,
+    receipts: RwLock<Vec<Receipt>>,
+    coordinates: RwLock<Vec<CoordinateRecord>>,
+    /// Serializes appends, so lines never interleave and a receipt is
+    /// admitted against the log it is appended to
+    append: tokio::sync::Mutex<()>,
+}
+
+impl FileBackend {
+    /// Open (creating if needed) the brain stored in `dir`
+    pub fn open(dir: impl AsRef<Path>) -> Result<Self, Error> {
+        let dir = dir.as_ref().to_path_buf();
+        std::fs::create_dir_all(&dir)?;
+        Ok(FileBackend {
+            receipts: RwLock::new(read_log(&dir.join(RECEIPTS_FILE))?),
+            coordinates: RwLock::new(read_log(&dir.join(COORDINATES_FILE))?),
+            dir,
+            append: tokio::sync::Mutex::new(()),
+        })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn len(&self) -> usize {
+        self.receipts.read().map(|r| r.len()).unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Record an emitted coordinate, stamped now if it has no timestamp
+    pub async fn record_coordinate(&self, mut record: CoordinateRecord) -> Result<CoordinateRecord, Error> {
+        let coordinate = record.coordinate.trim();
+        if !rules::is_coordinate(coordinate) && crate::a2ac::parse_expr(coordinate).is_err() {
+            return Err(BrainError::InvalidCoordinate(record.coordinate).into());
+        }
+        if record.timestamp == 0 {
+            record.timestamp = crate::now_unix();
+        }
+
+        let _guard = self.append.lock().await;
+        self.append_line(COORDINATES_FILE, &record).await?;
+        self.coordinates.write().map_err(poisoned)?.push(record.clone());
+        Ok(record)
+    }
+
+    /// Coordinates emitted under `trace_id`, oldest first
+    pub fn coordinates(&self, trace_id: &str) -> Result<Vec<CoordinateRecord>, Error> {
+        let coordinates = self.coordinates.read().map_err(poisoned)?;
+        Ok(coordinates.iter().filter(|c| c.trace_id == trace_id).cloned().collect())
+    }
+
+    /// Append one JSON line and fsync it, on a blocking thread so a slow
+    /// disk does not stall the runtime
+    async fn append_line<T: Serialize>(&self, file: &str, value: &T) -> Result<(), Error> {
+        let mut line = serde_json::to_vec(value)?;
+        line.push(b'\n');
+        let path = self.dir.join(file);
+        tokio::task::spawn_blocking(move || {
+            let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+            file.write_all(&line)?;
+            file.sync_data()
+        })
+        .await
+        .map_err(std::io::Error::other)??;
+        Ok(())
+    }
+}
+
+/// Check that `receipt` may be appended after `stored`
+fn admit(stored: &[Receipt], receipt: &Receipt) -> Result<(), BrainError> {
+    if stored.iter().any(|r| r.receipt_id == receipt.receipt_id) {
+        return Err(BrainError::Duplicate(receipt.receipt_id.clone()));
+    }
+    let rejected = |reason| BrainError::Rejected {
+        receipt_id: receipt.receipt_id.clone(),
+        reason,
+    };
+    if !receipt.verify_hash() {
+        return Err(rejected(ChainBreak::HashMismatch));
+    }
+
+    let head = stored.iter().rev().find(|r| r.trace_id == receipt.trace_id);
+    match (head, &receipt.prev_hash) {
+        (None, Some(link)) => Err(rejected(ChainBreak::NotGenesis(link.clone()))),
+        (Some(head), link) if link.as_deref() != Some(head.content_hash.as_str()) => {
+            Err(rejected(ChainBreak::BrokenLink {
+                expected: head.content_hash.clone(),
+                found: link.clone(),
+            }))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn read_log<T: serde::de::DeserializeOwned>(path: &Path) -> Result<Vec<T>, Error> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut entries = Vec::new();
+    for (i, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let entry = serde_json::from_str(&line).map_err(|e| BrainError::Corrupt {
+            path: path.to_path_buf(),
+            line: i + 1,
+            reason: e.to_string(),
+        })?;
+        entries.push(entry);
+    }
+    Ok(entries)
+}
+
+#[async_trait]
+impl BrainBackend for FileBackend {
+    async fn fetch_trace(&self, trace_id: &str) -> Result<Vec<Receipt>, Error> {
+        let receipts = self.receipts.read().map_err(poisoned)?;
+        Ok(receipts.iter().filter(|r| r.trace_id == trace_id).cloned().collect())
+    }
+
+    async fn fetch_receipt(&self, receipt_id: &str) -> Result<Option<Receipt>, Error> {
+        let receipts = self.receipts.read().map_err(poisoned)?;
+        Ok(receipts.iter().find(|r| r.receipt_id == receipt_id).cloned())
+    }
+
+    async fn store(&self, receipt: Receipt) -> Result<(), Error> {
+        let _guard = self.append.lock().await;
+        admit(&self.receipts.read().map_err(poisoned)?, &receipt)?;
+        self.append_line(RECEIPTS_FILE, &receipt).await?;
+        self.receipts.write().map_err(poisoned)?.push(receipt);
+        Ok(())
+    }
+
+    async fn query(&self, query: &MemQuery) -> Result<Vec<Receipt>, Error> {
+        let receipts = self.receipts.read().map_err(poisoned)?;
+        Ok(query.select(receipts.iter()).into_iter().cloned().collect())
+    }
+}
+
+// ============================================================================
+// HTTP BACKEND
+// ============================================================================
+
+/// Brain reached over HTTP(S) at a base URL
+#[derive(Debug, Clone)]
+pub struct HttpBackend {
+    base: Url,
+    timeout: Duration,
+    client: reqwest::Client,
+}
+
+impl HttpBackend {
+    pub fn new(base_url: &str) -> Result<Self, BrainError> {
+        let invalid = |reason: String| BrainError::InvalidUrl {
+            url: base_url.to_string(),
+            reason,
+        };
+        let base = Url::parse(base_url).map_err(|e| invalid(e.to_string()))?;
+        if !matches!(base.scheme(), "http" | "https") || base.cannot_be_a_base() {
+            return Err(invalid("expected an http(s) base url".to_string()));
+        }
+        Ok(HttpBackend {
+            base,
+            timeout: DEFAULT_TIMEOUT,
+            client: Self::client(base_url, DEFAULT_TIMEOUT)?,
+        })
+    }
+
+    /// Give up on any request after `timeout` (default 10s)
+    pub fn with_timeout(mut self, timeout: Duration) -> Result<Self, BrainError> {
+        self.client = Self::client(self.base.as_str(), timeout)?;
+        self.timeout = timeout;
+        Ok(self)
+    }
+
+    fn client(url: &str, timeout: Duration) -> Result<reqwest::Client, BrainError> {
+        reqwest::Client::builder()
+            .timeout(timeout)
+            .connect_timeout(timeout)
+            .build()
+            .map_err(|e| BrainError::Request {
+                url: url.to_string(),
+                reason: e.to_string(),
+            })
+    }
+
+    pub fn base_url(&self) -> &Url {
+        &self.base
+    }
+
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// `{base}/{segments…}`, each segment percent-encoded
+    fn url(&self, segments: &[&str]) -> Url {
+        let mut url = self.base.clone();
+        url.path_segments_mut()
+            .expect("checked in new")
+            .pop_if_empty()
+            .extend(segments);
+        url
+    }
+
+    fn error(&self, url: &Url, e: reqwest::Error) -> BrainError {
+        let url = url.to_string();
+        if e.is_timeout() {
+            BrainError::Timeout {
+                url,
+                timeout: self.timeout,
+            }
+        } else if e.is_connect() {
+            BrainError::Connect {
+                url,
+                reason: e.to_string(),
+            }
+        } else if e.is_decode() {
+            BrainError::Decode {
+                url,
+                reason: e.to_string(),
+            }
+        } else {
+            BrainError::Request {
+                url,
+                reason: e.to_string(),
+            }
+        }
+    }
+
+    /// GET `url` and decode the body; `None` on 404
+    async fn get<T: serde::de::DeserializeOwned>(&self, url: Url) -> Result<Option<T>, BrainError> {
+        let response = self.client.get(url.clone()).send().await.map_err(|e| self.error(&url, e))?;
+        match response.status() {
+            StatusCode::NOT_FOUND => Ok(None),
+            status if status.is_success() => {
+                let body = response.bytes().await.map_err(|e| self.error(&url, e))?;
+                serde_json::from_slice(&body).map(Some).map_err(|e| BrainError::Decode {
+                    url: url.to_string(),
+                    reason: e.to_string(),
+                })
+            }
+            status => Err(BrainError::Status {
+                url: url.to_string(),
+                status: status.as_u16(),
+            }),
+        }
+    }
+}
+
+#[async_trait]
+impl BrainBackend for HttpBackend {
+    async fn fetch_trace(&self, trace_id: &str) -> Result<Vec<Receipt>, Error> {
+        Ok(self.get(self.url(&["trace", trace_id])).await?.unwrap_or_default())
+    }
+
+    async fn fetch_receipt(&self, receipt_id: &str) -> Result<Option<Receipt>, Error> {
+        Ok(self.get(self.url(&["receipt", receipt_id])).await?)
+    }
+
+    async fn store(&self, receipt: Receipt) -> Result<(), Error> {
+        let url = self.url(&["receipt"]);
+        let response = self
+            .client
+            .post(url.clone())
+            .json(&receipt)
+            .send()
+            .await
+            .map_err(|e| self.error(&url, e))?;
+
+        #[derive(Deserialize)]
+        struct Rejection {
+            reason: ChainBreak,
+        }
+        let status = response.status();
+        let error = match status {
+            status if status.is_success() => return Ok(()),
+            StatusCode::CONFLICT => BrainError::Duplicate(receipt.receipt_id),
+            StatusCode::UNPROCESSABLE_ENTITY => match response.json::<Rejection>().await {
+                Ok(rejection) => BrainError::Rejected {
+                    receipt_id: receipt.receipt_id,
+                    reason: rejection.reason,
+                },
+                Err(_) => BrainError::Status {
+                    url: url.to_string(),
+                    status: status.as_u16(),
+                },
+            },
+            status => BrainError::Status {
+                url: url.to_string(),
+                status: status.as_u16(),
+            },
+        };
+        Err(error.into())
+    }
+
+    async fn query(&self, query: &MemQuery) -> Result<Vec<Receipt>, Error> {
+        let mut url = self.url(&["query"]);
+        url.query_pairs_mut().append_pair("pattern", &query.selection().to_string());
+        Ok(self.get(url).await?.unwrap_or_default())
+    }
+}
+
+/// Backend for `url`: an `HttpBackend`, or, if `url` cannot be one, a
+/// backend that fails every request with the reason. A bad URL then shows up
+/// as a bootstrap failure rather than passing for an empty brain.
+pub(crate) fn for_url(url: &str) -> std::sync::Arc<dyn BrainBackend> {
+    match HttpBackend::new(url) {
+        Ok(backend) => std::sync::Arc::new(backend),
+        Err(BrainError::InvalidUrl { url, reason }) => std::sync::Arc::new(InvalidUrl { url, reason }),
+        Err(e) => std::sync::Arc::new(InvalidUrl {
+            url: url.to_string(),
+            reason: e.to_string(),
+        }),
+    }
+}
+
+#[derive(Debug)]
+struct InvalidUrl {
+    url: String,
+    reason: String,
+}
+
+impl InvalidUrl {
+    fn error(&self) -> Error {
+        BrainError::InvalidUrl {
+            url: self.url.clone(),
+            reason: self.reason.clone(),
+        }
+        .into()
+    }
+}
+
+#[async_trait]
+impl BrainBackend for InvalidUrl {
+    async fn fetch_trace(&self, _: &str) -> Result<Vec<Receipt>, Error> {
+        Err(self.error())
+    }
+
+    async fn fetch_receipt(&self, _: &str) -> Result<Option<Receipt>, Error> {
+        Err(self.error())
+    }
+
+    async fn store(&self, _: Receipt) -> Result<(), Error> {
+        Err(self.error())
+    }
+
+    async fn query(&self, _: &MemQuery) -> Result<Vec<Receipt>, Error> {
+        Err(self.error())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// One-shot HTTP server: answers each connection with the next canned
+    /// response and hands back the request lines it saw
+    async fn serve(responses: Vec<(&'static str, String)>) -> (String, tokio::task::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}/brain/", listener.local_addr().unwrap());
+        let handle = tokio::spawn(async move {
+            let mut seen = Vec::new();
+            for (status, body) in responses {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut buf = vec![0; 4096];
+                let n = socket.read(&mut buf).await.unwrap();
+                let request = String::from_utf8_lossy(&buf[..n]);
+                seen.push(request.lines().next().unwrap_or_default().to_string());
+                if status.is_empty() {
+                    tokio::time::sleep(Duration::from_millis(500)).await;
+                    continue;
+                }
+                let reply = format!(
+                    "HTTP/1.1 {}\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                socket.write_all(reply.as_bytes()).await.unwrap();
+            }
+            seen
+        });
+        (base, handle)
+    }
+
+    #[tokio::test]
+    async fn test_file_backend_persists_chains() {
+        let dir = std::env::temp_dir().join(format!("q-brain-{}", crate::short_hex(b"file-backend")));
+        let mut log = crate::chain::ReceiptChain::new("trace_abc");
+        log.append(Receipt::success("git:clone:repo", "git-agent-001", "trace_abc", "cloned"));
+        log.append(Receipt::success("git:push:repo", "git-agent-001", "trace_abc", "pushed"));
+
+        let brain = FileBackend::open(&dir).unwrap();
+        for receipt in log.receipts() {
+            brain.store(receipt.clone()).await.unwrap();
+        }
+        let unlinked = Receipt::success("git:tag:repo", "git-agent-001", "trace_abc", "tagged");
+        assert!(matches!(
+            brain.store(unlinked).await,
+            Err(Error::Brain(BrainError::Rejected { reason: ChainBreak::BrokenLink { .. }, .. }))
+        ));
+        assert!(matches!(
+            brain.store(log.receipts()[0].clone()).await,
+            Err(Error::Brain(BrainError::Duplicate(_)))
+        ));
+        let coordinate = CoordinateRecord {
+            agent_id: "git-agent-001".to_string(),
+            trace_id: "trace_abc".to_string(),
+            coordinate: "◈ git:clone:repo".to_string(),
+            timestamp: 0,
+        };
+        brain.record_coordinate(coordinate.clone()).await.unwrap();
+        assert!(brain
+            .record_coordinate(CoordinateRecord { coordinate: "cloned it".to_string(), ..coordinate })
+            .await
+            .is_err());
+
+        let reopened = FileBackend::open(&dir).unwrap();
+        assert_eq!(reopened.fetch_trace("trace_abc").await.unwrap(), log.receipts());
+        assert_eq!(reopened.coordinates("trace_abc").unwrap().len(), 1);
+        assert_eq!(reopened.query(&MemQuery::pattern("git:clone")).await.unwrap().len(), 1);
+        assert!(reopened.query(&MemQuery::pattern("git:cl")).await.unwrap().is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_http_backend() {
+        let receipt = Receipt::success("git:clone:repo", "git-agent-001", "trace abc", "cloned");
+        let trace = serde_json::to_string(&[&receipt]).unwrap();
+        let (base, server) = serve(vec![
+            ("200 OK", trace),
+            ("404 Not Found", String::new()),
+            ("200 OK", "{\"receipt_id\": 1}".to_string()),
+            ("503 Service Unavailable", String::new()),
+            ("", String::new()),
+        ])
+        .await;
+
+        let brain = HttpBackend::new(&base).unwrap().with_timeout(Duration::from_millis(200)).unwrap();
+        assert_eq!(brain.fetch_trace("trace abc").await.unwrap(), vec![receipt.clone()]);
+        assert_eq!(brain.fetch_receipt("rcpt_missing").await.unwrap(), None);
+
+        let err = |r: Result<Option<Receipt>, Error>| match r {
+            Err(Error::Brain(e)) => e,
+            other => panic!("expected brain error, got {:?}", other),
+        };
+        assert!(matches!(err(brain.fetch_receipt("rcpt_bad").await), BrainError::Decode { .. }));
+        assert!(matches!(
+            err(brain.fetch_receipt("rcpt_down").await),
+            BrainError::Status { status: 503, .. }
+        ));
+        assert!(matches!(err(brain.fetch_receipt("rcpt_slow").await), BrainError::Timeout { .. }));
+
+        assert_eq!(
+            server.await.unwrap(),
+            [
+                "GET /brain/trace/trace%20abc HTTP/1.1",
+                "GET /brain/receipt/rcpt_missing HTTP/1.1",
+                "GET /brain/receipt/rcpt_bad HTTP/1.1",
+                "GET /brain/receipt/rcpt_down HTTP/1.1",
+                "GET /brain/receipt/rcpt_slow HTTP/1.1",
+            ]
+        );
+        assert!(matches!(HttpBackend::new("ftp://brain"), Err(BrainError::InvalidUrl { .. })));
+    }
+}
@@ -0,0 +1,220 @@
+// canonical.rs
+// Canonical JSON: the byte encoding every Q Protocol content hash is taken over
+//
+// A JCS-derived encoding: it follows RFC 8785 except where noted, so it is
+// not JCS and a JCS library will not reproduce these bytes.
+//   - No whitespace; object members sorted by key in Unicode code point
+//     order (JCS sorts by UTF-16 code units; the two can differ only for keys
+//     with characters above U+FFFF)
+//   - Members whose value is null are omitted, so `None` and "absent" hash
+//     alike (not in JCS)
+//   - Strings escape only `"`, `\` and control characters (\b \f \n \r \t,
+//     otherwise \u00xx); everything else is written as UTF-8
+//   - Integers are written verbatim, even beyond 2^53 (JCS would write them
+//     as the nearest double)
+//   - Floats use the ECMAScript Number-to-string form: shortest round-trip
+//     digits, no trailing ".0", exponent (`1e+21`, `1e-7`) outside [1e-6, 1e21);
+//     NaN and infinities are not representable and encode as null
+//   - Byte fields are standard, padded base64 strings (`bytes` below; not in
+//     JCS)
+//
+// Test vectors shared with the Python implementation
+// (`q_protocol_telemetry.canonical`) live in `definitions/canonical_vectors.json`.
+
+use serde::Serialize;
+use serde_json::{Map, Number, Value};
+
+/// Canonical encoding of `value`
+pub fn to_vec<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>, serde_json::Error> {
+    let value = serde_json::to_value(value)?;
+    let mut out = Vec::new();
+    write_value(&mut out, &value);
+    Ok(out)
+}
+
+/// Canonical encoding of `value` with the named top-level members left out,
+/// e.g. the hash field itself
+pub fn to_vec_excluding<T: Serialize + ?Sized>(value: &T, exclude: &[&str]) -> Result<Vec<u8>, serde_json::Error> {
+    let mut value = serde_json::to_value(value)?;
+    if let Value::Object(map) = &mut value {
+        for key in exclude {
+            map.remove(*key);
+        }
+    }
+    let mut out = Vec::new();
+    write_value(&mut out, &value);
+    Ok(out)
+}
+
+/// BLAKE3 of `bytes`, hex
+pub fn hash(bytes: &[u8]) -> String {
+    blake3::hash(bytes).to_hex().to_string()
+}
+
+fn write_value(out: &mut Vec<u8>, value: &Value) {
+    match value {
+        Value::Null => out.extend_from_slice(b"null"),
+        Value::Bool(b) => out.extend_from_slice(if *b { b"true" } else { b"false" }),
+        Value::Number(n) => write_number(out, n),
+        Value::String(s) => write_string(out, s),
+        Value::Array(items) => {
+            out.push(b'[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(b',');
+                }
+                write_value(out, item);
+            }
+            out.push(b']');
+        }
+        Value::Object(map) => write_object(out, map),
+    }
+}
+
+fn write_object(out: &mut Vec<u8>, map: &Map<String, Value>) {
+    // Byte order of UTF-8 keys is code point order
+    let mut members: Vec<(&String, &Value)> = map.iter().filter(|(_, v)| !v.is_null()).collect();
+    members.sort_by(|a, b| a.0.as_bytes().cmp(b.0.as_bytes()));
+
+    out.push(b'{');
+    for (i, (key, value)) in members.into_iter().enumerate() {
+        if i > 0 {
+            out.push(b',');
+        }
+        write_string(out, key);
+        out.push(b':');
+        write_value(out, value);
+    }
+    out.push(b'}');
+}
+
+fn write_string(out: &mut Vec<u8>, s: &str) {
+    // serde_json's escaping is exactly the JCS set
+    let _ = serde_json::to_writer(&mut *out, s);
+}
+
+fn write_number(out: &mut Vec<u8>, n: &Number) {
+    if let Some(i) = n.as_u64() {
+        out.extend_from_slice(i.to_string().as_bytes());
+    } else if let Some(i) = n.as_i64() {
+        out.extend_from_slice(i.to_string().as_bytes());
+    } else if let Some(f) = n.as_f64() {
+        out.extend_from_slice(format_float(f).as_bytes());
+    }
+}
+
+/// ECMAScript Number::toString for a finite f64
+pub(crate) fn format_float(f: f64) -> String {
+    if !f.is_finite() {
+        return "null".to_string();
+    }
+    if f == 0.0 {
+        return "0".to_string(); // Also -0
+    }
+
+    // `{:e}` gives the shortest round-trip digits: "-1.2345e-7"
+    let sci = format!("{:e}", f.abs());
+    let (mantissa, exp) = sci.split_once('e').unwrap_or((&sci, "0"));
+    let digits: String = mantissa.chars().filter(|c| *c != '.').collect();
+    let k = digits.len() as i32;
+    let n = exp.parse::<i32>().unwrap_or(0) + 1; // Decimal point position
+
+    let body = if k <= n && n <= 21 {
+        format!("{}{}", digits, "0".repeat((n - k) as usize))
+    } else if 0 < n && n <= 21 {
+        format!("{}.{}", &digits[..n as usize], &digits[n as usize..])
+    } else if -6 < n && n <= 0 {
+        format!("0.{}{}", "0".repeat((-n) as usize), digits)
+    } else {
+        let fraction = if k > 1 { format!(".{}", &digits[1..]) } else { String::new() };
+        let sign = if n - 1 < 0 { '-' } else { '+' };
+        format!("{}{}e{}{}", &digits[..1], fraction, sign, (n - 1).abs())
+    };
+
+    if f < 0.0 {
+        format!("-{}", body)
+    } else {
+        body
+    }
+}
+
+/// `#[serde(with = "canonical::bytes")]`: byte fields as padded base64 strings
+pub mod bytes {
+    use base64::engine::general_purpose::STANDARD as BASE64;
+    use base64::Engine;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&BASE64.encode(bytes))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        BASE64.decode(encoded).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cube::Cube;
+    use crate::qmem::{QMemCoordinate, QMemHeader, QMemReceipt, QMemState};
+    use crate::receipt::Receipt;
+    use serde::Deserialize;
+
+    const VECTORS: &str = include_str!("../../definitions/canonical_vectors.json");
+
+    #[derive(Deserialize)]
+    struct Vector {
+        name: String,
+        #[serde(rename = "type")]
+        kind: String,
+        input: Value,
+        canonical: String,
+        blake3: String,
+    }
+
+    fn encode_as(kind: &str, input: Value) -> Vec<u8> {
+        match kind {
+            "value" => to_vec(&input).unwrap(),
+            "Receipt" => serde_json::from_value::<Receipt>(input).unwrap().canonical_bytes(),
+            "Cube" => serde_json::from_value::<Cube>(input).unwrap().canonical_bytes(),
+            "QMemHeader" => serde_json::from_value::<QMemHeader>(input).unwrap().canonical_bytes(),
+            "QMemReceipt" => serde_json::from_value::<QMemReceipt>(input).unwrap().canonical_bytes(),
+            "QMemState" => serde_json::from_value::<QMemState>(input).unwrap().canonical_bytes(),
+            "QMemCoordinate" => serde_json::from_value::<QMemCoordinate>(input).unwrap().canonical_bytes(),
+            other => panic!("unknown vector type {}", other),
+        }
+    }
+
+    #[test]
+    fn test_vectors() {
+        let vectors: Vec<Vector> = serde_json::from_str(VECTORS).unwrap();
+        assert!(!vectors.is_empty());
+
+        for v in vectors {
+            let bytes = encode_as(&v.kind, v.input);
+            assert_eq!(String::from_utf8(bytes.clone()).unwrap(), v.canonical, "{}", v.name);
+            assert_eq!(hash(&bytes), v.blake3, "{}", v.name);
+        }
+    }
+
+    #[test]
+    fn test_float_format() {
+        let cases = [
+            (1.0, "1"),
+            (-2.5, "-2.5"),
+            (0.1, "0.1"),
+            (1e21, "1e+21"),
+            (1e20, "100000000000000000000"),
+            (1e-7, "1e-7"),
+            (0.000001, "0.000001"),
+            (123.456e-10, "1.23456e-8"),
+            (-0.0, "0"),
+            (f64::NAN, "null"),
+        ];
+        for (f, expected) in cases {
+            assert_eq!(format_float(f), expected, "{:?}", f);
+        }
+    }
+}
@@ -0,0 +1,209 @@
+// chain.rs
+// Append-only receipt log per trace: every receipt carries the content hash
+// of its predecessor (`prev_hash`), so deleting, reordering or editing a
+// receipt breaks a link the verifier can point at
+//
+//   genesis (prev_hash: none) ← r1 (prev_hash: genesis) ← r2 (prev_hash: r1) …
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::keyring::AgentSigningKey;
+use crate::receipt::Receipt;
+
+/// What is wrong with a link
+#[derive(Debug, Clone, PartialEq, Eq, Error, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChainBreak {
+    #[error("contents do not match the stored hash")]
+    HashMismatch,
+
+    #[error("belongs to trace {0}")]
+    ForeignTrace(String),
+
+    #[error("first receipt links to {0}; earlier receipts are missing")]
+    NotGenesis(String),
+
+    #[error("expected link to {expected}, found {}", found.as_deref().unwrap_or("none"))]
+    BrokenLink {
+        expected: String,
+        found: Option<String>,
+    },
+}
+
+/// First broken link in a trace
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("receipt chain broken at #{index} ({receipt_id}): {kind}")]
+pub struct ChainError {
+    pub index: usize,
+    pub receipt_id: String,
+    pub kind: ChainBreak,
+}
+
+/// Walk `receipts` (oldest first) and report the first broken link
+pub fn verify_chain(trace_id: &str, receipts: &[Receipt]) -> Result<(), ChainError> {
+    let mut prev: Option<&Receipt> = None;
+
+    for (index, receipt) in receipts.iter().enumerate() {
+        let broken = |kind| ChainError {
+            index,
+            receipt_id: receipt.receipt_id.clone(),
+            kind,
+        };
+
+        if receipt.trace_id != trace_id {
+            return Err(broken(ChainBreak::ForeignTrace(receipt.trace_id.clone())));
+        }
+        if !receipt.verify_hash() {
+            return Err(broken(ChainBreak::HashMismatch));
+        }
+        match (prev, &receipt.prev_hash) {
+            (None, None) => {}
+            (None, Some(link)) => return Err(broken(ChainBreak::NotGenesis(link.clone()))),
+            (Some(p), link) if link.as_deref() != Some(p.content_hash.as_str()) => {
+                return Err(broken(ChainBreak::BrokenLink {
+                    expected: p.content_hash.clone(),
+                    found: link.clone(),
+                }))
+            }
+            _ => {}
+        }
+        prev = Some(receipt);
+    }
+    Ok(())
+}
+
+// ============================================================================
+// CHAIN
+// ============================================================================
+
+/// A trace's receipts, linked as they are appended
+#[derive(Debug, Clone, Default)]
+pub struct ReceiptChain {
+    trace_id: String,
+    receipts: Vec<Receipt>,
+}
+
+impl ReceiptChain {
+    pub fn new(trace_id: &str) -> Self {
+        ReceiptChain {
+            trace_id: trace_id.to_string(),
+            receipts: Vec::new(),
+        }
+    }
+
+    /// Adopt an existing log, refusing one that does not verify
+    pub fn from_receipts(trace_id: &str, receipts: Vec<Receipt>) -> Result<Self, ChainError> {
+        verify_chain(trace_id, &receipts)?;
+        Ok(ReceiptChain {
+            trace_id: trace_id.to_string(),
+            receipts,
+        })
+    }
+
+    /// Link `receipt` to the head and append it; the receipt is moved into
+    /// this chain's trace and resealed
+    pub fn append(&mut self, receipt: Receipt) -> &Receipt {
+        let linked = self.link(receipt);
+        self.push(linked)
+    }
+
+    /// `append`, then sign the linked receipt
+    pub fn append_signed(&mut self, receipt: Receipt, key: &AgentSigningKey) -> &Receipt {
+        let linked = self.link(receipt).signed(key);
+        self.push(linked)
+    }
+
+    fn link(&self, receipt: Receipt) -> Receipt {
+        Receipt {
+            trace_id: self.trace_id.clone(),
+            ..receipt
+        }
+        .linked_to(self.head().map(|r| r.content_hash.clone()))
+    }
+
+    fn push(&mut self, receipt: Receipt) -> &Receipt {
+        self.receipts.push(receipt);
+        &self.receipts[self.receipts.len() - 1]
+    }
+
+    pub fn head(&self) -> Option<&Receipt> {
+        self.receipts.last()
+    }
+
+    pub fn receipts(&self) -> &[Receipt] {
+        &self.receipts
+    }
+
+    pub fn trace_id(&self) -> &str {
+        &self.trace_id
+    }
+
+    pub fn len(&self) -> usize {
+        self.receipts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.receipts.is_empty()
+    }
+
+    pub fn verify(&self) -> Result<(), ChainError> {
+        verify_chain(&self.trace_id, &self.receipts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chain() -> ReceiptChain {
+        let mut chain = ReceiptChain::new("trace_abc");
+        for op in ["git:clone:repo", "test:run:repo", "git:push:repo"] {
+            chain.append(Receipt::success(op, "git-agent-001", "trace_abc", "ok"));
+        }
+        chain
+    }
+
+    #[test]
+    fn test_chain_links() {
+        let chain = chain();
+        assert!(chain.verify().is_ok());
+        assert_eq!(chain.receipts()[0].prev_hash, None);
+        assert_eq!(chain.receipts()[2].prev_hash.as_ref(), Some(&chain.receipts()[1].content_hash));
+
+        let signed = {
+            let mut chain = chain.clone();
+            let key = AgentSigningKey::from_bytes("git-agent-001#1", &[1; 32]);
+            chain.append_signed(Receipt::new("git:tag:repo", "git-agent-001", "trace_abc", true), &key);
+            chain
+        };
+        assert!(signed.head().unwrap().signature.is_some());
+        assert!(signed.verify().is_ok());
+    }
+
+    #[test]
+    fn test_first_broken_link() {
+        let receipts = chain().receipts().to_vec();
+
+        // Deleted from the middle
+        let deleted = [receipts[0].clone(), receipts[2].clone()];
+        let err = verify_chain("trace_abc", &deleted).unwrap_err();
+        assert_eq!((err.index, err.receipt_id.as_str()), (1, receipts[2].receipt_id.as_str()));
+        assert!(matches!(err.kind, ChainBreak::BrokenLink { .. }));
+
+        // Reordered
+        let reordered = [receipts[0].clone(), receipts[2].clone(), receipts[1].clone()];
+        assert_eq!(verify_chain("trace_abc", &reordered).unwrap_err().index, 1);
+
+        // Head deleted
+        let err = verify_chain("trace_abc", &receipts[1..]).unwrap_err();
+        assert!(matches!(err.kind, ChainBreak::NotGenesis(_)));
+
+        // Edited in place
+        let mut edited = receipts.clone();
+        edited[1].result = Some("failed".to_string());
+        assert_eq!(verify_chain("trace_abc", &edited).unwrap_err().kind, ChainBreak::HashMismatch);
+
+        assert!(ReceiptChain::from_receipts("trace_other", receipts).is_err());
+    }
+}
@@ -0,0 +1,133 @@
+// citation.rs
+// Receipt references in free text, in every syntax agents cite them with
+//
+//   ◈ RECEIPT:rcpt_abc123            A2AC(md), in a chain or bare in prose
+//   receipt_id: rcpt_abc123          key/value field (also receipt-id=, receiptId:)
+//   "receiptId": "RCPT-c8f1a2b7cc70" JSON member (also "receipt_id")
+//   0x600:04:CASE:RSLV:a1b2c3        hex coordinate in the RECEIPT space
+//
+// Field and JSON ids run to the first character outside [A-Za-z0-9_.:-], so
+// prefixed ids ("RCPT-…", "rcpt_…") come through whole. When two syntaxes
+// cover the same bytes (a hex coordinate quoted as a JSON value), the
+// reference that starts first wins.
+//
+// A hex coordinate names an operation rather than a receipt; `DayZero`
+// resolves it to the operation's latest successful receipt before verifying.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+
+use crate::a2ac::{self, Span};
+use crate::hex_coordinate::{HexCoordinate, SpaceCode};
+
+static FIELD: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?i)\breceipt[_-]?id\s*[:=]\s*['"]?([A-Za-z0-9_.:-]*[A-Za-z0-9_-])"#).unwrap()
+});
+
+static JSON: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#""(?:receiptId|receipt_id)"\s*:\s*"([^"\\\s]+)""#).unwrap()
+});
+
+static HEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\b0[xX]600:04:[A-Za-z]{4}:[A-Za-z]{4}:[0-9A-Fa-f]+\b").unwrap());
+
+/// How a receipt was cited
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CitationSyntax {
+    /// `RECEIPT:id`
+    A2ac,
+    /// `receipt_id: id`
+    Field,
+    /// `"receiptId": "id"`
+    Json,
+    /// `0x600:04:…`
+    Hex,
+}
+
+/// One receipt reference: the id to look up and where it was cited
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Citation {
+    /// The cited id; for `Hex`, the coordinate itself
+    pub receipt_id: String,
+    pub syntax: CitationSyntax,
+    /// The whole reference, keyword or key included
+    pub span: Span,
+}
+
+/// Every receipt reference in `text`, in order of appearance
+pub fn find_all(text: &str) -> Vec<Citation> {
+    let mut found: Vec<Citation> = a2ac::scan(text)
+        .iter()
+        .flat_map(|chain| chain.receipts())
+        .map(|r| Citation {
+            receipt_id: r.id.clone(),
+            syntax: CitationSyntax::A2ac,
+            span: r.span,
+        })
+        .collect();
+
+    for (regex, syntax) in [(&*FIELD, CitationSyntax::Field), (&*JSON, CitationSyntax::Json)] {
+        found.extend(regex.captures_iter(text).map(|caps| {
+            let whole = caps.get(0).unwrap();
+            Citation {
+                receipt_id: caps[1].to_string(),
+                syntax,
+                span: Span::new(whole.start(), whole.end()),
+            }
+        }));
+    }
+
+    found.extend(HEX.find_iter(text).filter_map(|m| {
+        let coord = m.as_str().parse::<HexCoordinate>().ok()?;
+        (coord.space() == SpaceCode::Receipt).then(|| Citation {
+            receipt_id: coord.to_string(),
+            syntax: CitationSyntax::Hex,
+            span: Span::new(m.start(), m.end()),
+        })
+    }));
+
+    found.sort_by_key(|c| (c.span.start, std::cmp::Reverse(c.span.end)));
+    let mut kept: Vec<Citation> = Vec::with_capacity(found.len());
+    for citation in found {
+        if kept.last().is_none_or(|last| citation.span.start >= last.span.end) {
+            kept.push(citation);
+        }
+    }
+    kept
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_syntax() {
+        let text = concat!(
+            "Cloned (◈ RECEIPT:rcpt_clone), tested with receipt_id: RCPT-c8f1a2b7cc70, ",
+            r#"pushed {"receiptId": "RCPT-0a1b2c3d4e5f"} and resolved 0x600:04:CASE:RSLV:a1b2c3."#
+        );
+        let found = find_all(text);
+        let ids: Vec<_> = found.iter().map(|c| (c.syntax, c.receipt_id.as_str())).collect();
+        assert_eq!(
+            ids,
+            [
+                (CitationSyntax::A2ac, "rcpt_clone"),
+                (CitationSyntax::Field, "RCPT-c8f1a2b7cc70"),
+                (CitationSyntax::Json, "RCPT-0a1b2c3d4e5f"),
+                (CitationSyntax::Hex, "0x600:04:CASE:RSLV:a1b2c3"),
+            ]
+        );
+        assert_eq!(&text[found[1].span.start..found[1].span.end], "receipt_id: RCPT-c8f1a2b7cc70");
+
+        // Overlapping syntaxes are reported once
+        let json = r#"{"receipt_id": "0x600:04:CASE:RSLV:a1b2c3"}"#;
+        let found = find_all(json);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].syntax, CitationSyntax::Json);
+
+        // Other spaces and malformed coordinates are not receipts
+        assert!(find_all("acting on 0x600:02:CASE:RSLV:a1b2c3, see 0x600:04:CASE:RSLV:zz").is_empty());
+        assert!(find_all("the receipt is pending").is_empty());
+    }
+}
@@ -0,0 +1,156 @@
+// cube.rs
+// Memory cubes and the day_zero cube wrapper
+
+use serde::{Deserialize, Serialize};
+
+use crate::canonical;
+use crate::enforce::DayZero;
+use crate::{now_unix, short_id};
+
+// ============================================================================
+// CUBE
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Cube {
+    pub cube_id: String,
+    pub cube_type: CubeType,
+    #[serde(with = "canonical::bytes")]
+    pub payload: Vec<u8>,
+    /// BLAKE3 of `canonical_bytes()`, hex
+    pub content_hash: String,
+    pub source: String,
+    pub target: Option<String>,
+    pub trace_id: String,
+    pub timestamp: u64,
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CubeType {
+    Message,
+    Receipt,
+    State,
+    Coordinate,
+    Agent,
+}
+
+impl Cube {
+    /// Create a cube from `source`, sealed with its BLAKE3 content hash
+    pub fn new(cube_type: CubeType, payload: Vec<u8>, source: &str, trace_id: &str) -> Self {
+        Cube {
+            cube_id: short_id("cube", blake3::hash(&payload).as_bytes()),
+            cube_type,
+            payload,
+            content_hash: String::new(),
+            source: source.to_string(),
+            target: None,
+            trace_id: trace_id.to_string(),
+            timestamp: now_unix(),
+            tags: Vec::new(),
+        }
+        .sealed()
+    }
+
+    pub fn with_target(mut self, target: &str) -> Self {
+        self.target = Some(target.to_string());
+        self.sealed()
+    }
+
+    pub fn with_tag(mut self, tag: &str) -> Self {
+        self.tags.push(tag.to_string());
+        self.sealed()
+    }
+
+    /// Canonical encoding (see `canonical`) of every field except `content_hash`
+    pub fn canonical_bytes(&self) -> Vec<u8> {
+        canonical::to_vec_excluding(self, &["content_hash"]).unwrap_or_default()
+    }
+
+    pub fn compute_hash(&self) -> String {
+        canonical::hash(&self.canonical_bytes())
+    }
+
+    /// Store the hash of the current contents
+    pub fn sealed(mut self) -> Self {
+        self.content_hash = self.compute_hash();
+        self
+    }
+
+    /// Check the stored hash against the contents
+    pub fn verify_hash(&self) -> bool {
+        self.content_hash == self.compute_hash()
+    }
+}
+
+// ============================================================================
+// CUBE WRAPPER
+// ============================================================================
+
+/// Attach day_zero to a cube
+pub struct DayZeroCube {
+    cube: Cube,
+    enforcer: DayZero,
+}
+
+impl DayZeroCube {
+    pub fn wrap(cube: Cube, brain_url: String) -> Self {
+        let enforcer = DayZero::new(
+            cube.source.clone(),
+            cube.trace_id.clone(),
+            brain_url,
+        );
+
+        DayZeroCube { cube, enforcer }
+    }
+
+    pub async fn process_message(&mut self, message: &str) -> Result<String, String> {
+        match self.enforcer.process_outgoing(message).await {
+            Ok(optimized) => Ok(optimized),
+            Err(_) => {
+                // Don't block (training mode); the violations are already
+                // recorded in the enforcer's metrics
+                Ok(self.enforcer.optimize_message(message))
+            }
+        }
+    }
+
+    pub fn cube(&self) -> &Cube {
+        &self.cube
+    }
+
+    pub fn enforcer(&self) -> &DayZero {
+        &self.enforcer
+    }
+
+    pub fn check_graduation(&self) -> bool {
+        self.enforcer.check_graduation()
+    }
+
+    pub fn print_report(&self) {
+        self.enforcer.print_report();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cube_hash_roundtrip() {
+        let cube = Cube::new(CubeType::Message, "◈ git:clone:repo".as_bytes().to_vec(), "git-agent-001", "trace_abc")
+            .with_target("analyze-agent")
+            .with_tag("git");
+
+        assert!(cube.cube_id.starts_with("cube_"));
+        assert!(cube.verify_hash());
+
+        let mut tampered = cube.clone();
+        tampered.payload = "◈ git:clone:other".as_bytes().to_vec();
+        assert!(!tampered.verify_hash());
+
+        let mut rerouted = cube.clone();
+        rerouted.target = Some("other-agent".to_string());
+        assert!(!rerouted.verify_hash());
+    }
+}
@@ -0,0 +1,1330 @@
+// enforce.rs (formerly definitions/day_zero.rs)
+// Q Protocol Runtime Enforcement Layer
+// Attached to every memory cube until A2AC self-enforcement achieved
+//
+// Purpose: Training wheels for K→0 coordination
+// Lifecycle: Required until agents achieve 95%+ Q Protocol compliance
+// Then: Deprecated in favor of learned A2AC behavior
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::a2ac::Span;
+use crate::chain;
+use crate::citation::{self, Citation, CitationSyntax};
+use crate::brain::{self, BrainBackend};
+use crate::error::Error;
+use crate::freshness::{FreshnessPolicy, Staleness, INVALIDATE_PREFIX};
+use crate::hex_coordinate::HexCoordinate;
+use crate::keyring::Keyring;
+use crate::metrics::{
+    ClaimOutcome, DayZeroMetrics, UnbackedClaim, TARGET_COORDINATE_USAGE, TARGET_K_VALUE,
+    TARGET_RECEIPT_COVERAGE,
+};
+use crate::policy::PolicySettings;
+use crate::query::{matches_operation, MemQuery, QueryOutput};
+use crate::receipt::{Receipt, ReceiptVerification};
+use crate::rewrite::{Edit, Rewrite};
+use crate::rules::{self, ReceiptCheck, ReceiptsAreTruthRule, RuleContext, RulePhase, RuleSet};
+use crate::state::StateCache;
+use crate::tokenizer::{HeuristicTokenizer, Tokenizer};
+use crate::translate::{CoordinateRegistry, TranslateError, Translator};
+
+// ============================================================================
+// PROTOCOL VIOLATIONS
+// ============================================================================
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProtocolViolation {
+    /// Stable rule code, e.g. "NO_SPECULATION" (see `rules`)
+    pub rule: String,
+    pub severity: ViolationSeverity,
+    pub message: String,
+    /// Offending bytes of the checked message, if the rule can point at them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub span: Option<Span>,
+    pub token_waste: usize,
+    /// Suggested edit that resolves the violation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fix: Option<Box<Edit>>,
+}
+
+impl ProtocolViolation {
+    pub fn new(
+        severity: ViolationSeverity,
+        rule: impl Into<String>,
+        message: impl Into<String>,
+        token_waste: usize,
+    ) -> Self {
+        ProtocolViolation {
+            rule: rule.into(),
+            severity,
+            message: message.into(),
+            span: None,
+            token_waste,
+            fix: None,
+        }
+    }
+
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.span = Some(span);
+        self
+    }
+
+    pub fn with_fix(mut self, fix: Edit) -> Self {
+        self.fix = Some(Box::new(fix));
+        self
+    }
+}
+
+impl std::fmt::Display for ProtocolViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "[{:?}] {} - {} (waste: {} tokens)",
+            self.severity, self.rule, self.message, self.token_waste
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ViolationSeverity {
+    Info,      // Suggestion for improvement
+    #[serde(alias = "warn")]
+    Warning,   // Suboptimal but acceptable
+    Error,     // Protocol violation
+    Critical,  // Hallucination or amnesia detected
+}
+
+impl std::fmt::Display for ViolationSeverity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            ViolationSeverity::Info => "info",
+            ViolationSeverity::Warning => "warning",
+            ViolationSeverity::Error => "error",
+            ViolationSeverity::Critical => "critical",
+        };
+        f.write_str(name)
+    }
+}
+
+impl std::str::FromStr for ViolationSeverity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "info" => Ok(ViolationSeverity::Info),
+            "warning" | "warn" => Ok(ViolationSeverity::Warning),
+            "error" => Ok(ViolationSeverity::Error),
+            "critical" => Ok(ViolationSeverity::Critical),
+            other => Err(format!("unknown severity: {}", other)),
+        }
+    }
+}
+
+/// Outcome of enforcing a single outgoing message
+#[derive(Debug, Clone)]
+pub struct Evaluation {
+    /// Optimized message, or `None` if strict mode blocked it
+    pub optimized: Option<String>,
+    /// Edits and diff behind `optimized`; `None` if blocked
+    pub rewrite: Option<Rewrite>,
+    pub violations: Vec<ProtocolViolation>,
+}
+
+impl Evaluation {
+    pub fn is_blocked(&self) -> bool {
+        self.optimized.is_none()
+    }
+
+    /// Highest severity among the violations, if any
+    pub fn max_severity(&self) -> Option<ViolationSeverity> {
+        self.violations.iter().map(|v| v.severity).max()
+    }
+}
+
+// ============================================================================
+// DAY ZERO ENFORCER
+// ============================================================================
+
+pub struct DayZero {
+    agent_id: String,
+    trace_id: String,
+    brain_url: String,
+    state_cache: StateCache,
+    freshness: FreshnessPolicy,
+    backend: Arc<dyn BrainBackend>,
+    keyring: Option<Arc<Keyring>>,
+    translator: Translator,
+    rules: RuleSet,
+    tokenizer: Box<dyn Tokenizer>,
+    metrics: DayZeroMetrics,
+    strict_mode: bool, // If true, block violations; if false, warn only
+}
+
+impl DayZero {
+    /// Enforcer for `agent_id` in `trace_id`, reading receipts from the
+    /// brain at `brain_url` over HTTP (see `with_backend` to use another)
+    pub fn new(agent_id: String, trace_id: String, brain_url: String) -> Self {
+        DayZero {
+            agent_id,
+            trace_id,
+            backend: brain::for_url(&brain_url),
+            brain_url,
+            state_cache: StateCache::new(),
+            freshness: FreshnessPolicy::builtin(),
+            keyring: None,
+            translator: Translator::new(CoordinateRegistry::builtin()),
+            rules: RuleSet::builtin(),
+            tokenizer: Box::new(HeuristicTokenizer),
+            metrics: DayZeroMetrics::new(),
+            strict_mode: false,
+        }
+    }
+
+    /// Block messages with critical violations instead of warning only
+    pub fn with_strict_mode(mut self, strict_mode: bool) -> Self {
+        self.strict_mode = strict_mode;
+        self
+    }
+
+    /// Judge graduation on the last `messages` messages (default 100)
+    pub fn with_graduation_window(mut self, messages: usize) -> Self {
+        self.metrics.set_window_size(messages);
+        self
+    }
+
+    /// Fetch and verify receipts through `backend` (default: an `HttpBackend`
+    /// for `brain_url`)
+    pub fn with_backend(mut self, backend: Arc<dyn BrainBackend>) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    pub fn backend(&self) -> &Arc<dyn BrainBackend> {
+        &self.backend
+    }
+
+    /// When receipts stop counting as prior work (default: `FreshnessPolicy::builtin()`)
+    pub fn with_freshness(mut self, freshness: FreshnessPolicy) -> Self {
+        self.freshness = freshness;
+        self
+    }
+
+    pub fn freshness(&self) -> &FreshnessPolicy {
+        &self.freshness
+    }
+
+    /// Check receipt signatures against `keyring`; unsigned receipts only pass
+    /// for agents the keyring has no key for
+    pub fn with_keyring(mut self, keyring: Arc<Keyring>) -> Self {
+        self.keyring = Some(keyring);
+        self
+    }
+
+    pub fn keyring(&self) -> Option<&Keyring> {
+        self.keyring.as_deref()
+    }
+
+    /// Add subject/action mappings so semantic and hex coordinates share receipts
+    pub fn with_registry(mut self, registry: &CoordinateRegistry) -> Result<Self, TranslateError> {
+        let mut merged = self.translator.registry().clone();
+        merged.extend(registry)?;
+        self.translator = Translator::new(merged);
+        Ok(self)
+    }
+
+    /// Replace the enforcement rules (default: `RuleSet::builtin()`)
+    pub fn with_rules(mut self, rules: RuleSet) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    /// Configure the built-in rules and strict mode from a resolved policy.
+    /// The rules are reconfigured in place (see `PolicySettings::configure`),
+    /// so rules added before with `with_rules` or `rules_mut` are kept.
+    pub fn with_policy(mut self, policy: &PolicySettings) -> Self {
+        policy.configure(&mut self.rules);
+        if let Some(window) = policy.limits.graduation_window {
+            self.metrics.set_window_size(window);
+        }
+        if let Some(rules) = &policy.freshness {
+            self.freshness = FreshnessPolicy::new(rules.clone());
+        }
+        if let Some(strict) = policy.strict {
+            self.strict_mode = strict;
+        }
+        self
+    }
+
+    pub fn rules(&self) -> &RuleSet {
+        &self.rules
+    }
+
+    /// Add, disable or reorder rules in place
+    pub fn rules_mut(&mut self) -> &mut RuleSet {
+        &mut self.rules
+    }
+
+    /// Count tokens for the target model (default: `HeuristicTokenizer`)
+    pub fn with_tokenizer(mut self, tokenizer: Box<dyn Tokenizer>) -> Self {
+        self.tokenizer = tokenizer;
+        self
+    }
+
+    pub fn tokenizer(&self) -> &dyn Tokenizer {
+        self.tokenizer.as_ref()
+    }
+
+    pub fn translator(&self) -> &Translator {
+        &self.translator
+    }
+
+    pub fn agent_id(&self) -> &str {
+        &self.agent_id
+    }
+
+    pub fn trace_id(&self) -> &str {
+        &self.trace_id
+    }
+
+    pub fn brain_url(&self) -> &str {
+        &self.brain_url
+    }
+
+    pub fn is_strict(&self) -> bool {
+        self.strict_mode
+    }
+
+    // ========================================================================
+    // PROTOCOL #1: SILENCE IS SUCCESS
+    // ========================================================================
+
+    /// Enforce token minimization
+    pub fn enforce_silence(&self, message: &str) -> Result<(), Vec<ProtocolViolation>> {
+        let ctx = RuleContext {
+            message,
+            token_count: self.count_tokens(message),
+            receipts: &[],
+        };
+        let violations = self.rules.check(RulePhase::Silence, &ctx);
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+
+    // ========================================================================
+    // PROTOCOL #2: RECEIPTS ARE TRUTH
+    // ========================================================================
+
+    /// Validate receipt claims
+    pub async fn enforce_receipts(&self, message: &str) -> Result<(), Vec<ProtocolViolation>> {
+        let (_, violations) = self.check_receipts(message).await;
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+
+    /// Verify the receipts `message` cites and run the receipt rules
+    async fn check_receipts(&self, message: &str) -> (Vec<ReceiptCheck>, Vec<ProtocolViolation>) {
+        // A receipt cited more than once is fetched once
+        let mut verified: HashMap<String, (String, Result<ReceiptVerification, String>)> = HashMap::new();
+        let mut receipts = Vec::new();
+        for citation in self.extract_receipts(message) {
+            if !verified.contains_key(&citation.receipt_id) {
+                let result = match self.resolve_citation(&citation).await {
+                    Ok(Some(receipt_id)) => {
+                        let verification = self.verify_receipt(&receipt_id).await.map_err(|e| e.to_string());
+                        (receipt_id, verification)
+                    }
+                    Ok(None) => (citation.receipt_id.clone(), Ok(ReceiptVerification::Missing)),
+                    Err(e) => (citation.receipt_id.clone(), Err(e.to_string())),
+                };
+                verified.insert(citation.receipt_id.clone(), result);
+            }
+            let (receipt_id, verification) = verified[&citation.receipt_id].clone();
+            receipts.push(ReceiptCheck {
+                receipt_id,
+                span: citation.span,
+                verified: verification,
+            });
+        }
+
+        let ctx = RuleContext {
+            message,
+            token_count: self.count_tokens(message),
+            receipts: &receipts,
+        };
+        let violations = self.rules.check(RulePhase::Receipts, &ctx);
+        (receipts, violations)
+    }
+
+    /// Whether `message` claims completion, in words as the configured
+    /// `RECEIPTS_ARE_TRUTH` rule reads them, and whether every receipt it
+    /// cites verified. Citing a receipt without a claim phrase is not a claim
+    fn classify_claim(&self, message: &str, receipts: &[ReceiptCheck]) -> Option<ClaimOutcome> {
+        let phrase = self
+            .rules
+            .get::<ReceiptsAreTruthRule>(rules::RECEIPTS_ARE_TRUTH)
+            .and_then(|rule| rule.find_claim(message));
+        let span = phrase?;
+        let message_number = self.metrics.total_messages + 1;
+
+        if receipts.is_empty() {
+            return Some(ClaimOutcome::Unbacked(UnbackedClaim {
+                message: message_number,
+                claim: message[span.start..span.end].to_string(),
+                span,
+                reason: "no receipt cited".to_string(),
+            }));
+        }
+
+        let outcome = match receipts
+            .iter()
+            .find(|check| !matches!(check.verified, Ok(ReceiptVerification::Valid)))
+        {
+            None => ClaimOutcome::Backed,
+            Some(check) => ClaimOutcome::Unbacked(UnbackedClaim {
+                message: message_number,
+                claim: message[check.span.start..check.span.end].to_string(),
+                span: check.span,
+                reason: match &check.verified {
+                    Ok(verification) => format!("receipt {} {}", check.receipt_id, verification),
+                    Err(e) => format!("receipt {} unverifiable: {}", check.receipt_id, e),
+                },
+            }),
+        };
+        Some(outcome)
+    }
+
+    // ========================================================================
+    // PROTOCOL #3: QUERY BEFORE ACT
+    // ========================================================================
+
+    /// Enforce mandatory state querying
+    pub async fn enforce_bootstrap(&mut self) -> Result<(), Vec<ProtocolViolation>> {
+        let mut violations = Vec::new();
+
+        // Rule 1: Every session must query state on init
+        if self.state_cache.is_empty() && self.metrics.total_messages == 0 {
+            // First message - must be state query
+            let state = self.query_brain_state().await;
+            
+            match state {
+                Ok(receipts) => {
+                    // Only trust state whose chain is intact
+                    if let Err(e) = chain::verify_chain(&self.trace_id, &receipts) {
+                        violations.push(ProtocolViolation::new(
+                            ViolationSeverity::Critical,
+                            "QUERY_BEFORE_ACT",
+                            format!("Brain state for {} not trusted: {}. TAMPER RISK.", self.trace_id, e),
+                            0,
+                        ));
+                        return Err(violations);
+                    }
+
+                    // Cache state
+                    for receipt in receipts {
+                        let key = self.translator.operation_key(&receipt.operation);
+                        self.state_cache.insert(key, receipt);
+                    }
+                }
+                Err(e) => {
+                    violations.push(ProtocolViolation::new(
+                        ViolationSeverity::Critical,
+                        "QUERY_BEFORE_ACT",
+                        format!("Bootstrap query failed: {}. AMNESIA RISK.", e),
+                        0,
+                    ));
+                }
+            }
+        }
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+
+    /// Check if operation already done: its latest successful receipt, if
+    /// that is still fresh
+    pub fn check_prior_work(&self, operation: &str) -> Option<&Receipt> {
+        self.state_cache
+            .latest_success(&self.translator.operation_key(operation))
+            .filter(|receipt| self.staleness(receipt).is_none())
+    }
+
+    /// Why a cached receipt no longer counts as prior work; `None` if it does
+    pub fn staleness(&self, receipt: &Receipt) -> Option<Staleness> {
+        let later = self
+            .state_cache
+            .receipts()
+            .skip_while(|r| r.receipt_id != receipt.receipt_id)
+            .skip(1);
+        self.freshness
+            .staleness(receipt, later, crate::now_unix(), |p, r| self.covers(p, r))
+    }
+
+    /// Whether an operation pattern covers `receipt`, in either dialect
+    fn covers(&self, pattern: &str, receipt: &Receipt) -> bool {
+        matches_operation(pattern, &receipt.operation)
+            || matches_operation(pattern, &self.translator.operation_key(&receipt.operation))
+    }
+
+    /// The operation's most recent receipt, successful or not
+    pub fn latest_attempt(&self, operation: &str) -> Option<&Receipt> {
+        self.state_cache.latest_attempt(&self.translator.operation_key(operation))
+    }
+
+    /// Every receipt for the operation, oldest first
+    pub fn receipt_history(&self, operation: &str) -> &[Receipt] {
+        self.state_cache.history(&self.translator.operation_key(operation))
+    }
+
+    /// Answer a `MEM:QUERY` from the state loaded on bootstrap; stale
+    /// receipts are left out. Patterns match an operation in either dialect,
+    /// as freshness rules do.
+    pub fn query_state(&self, query: &MemQuery) -> QueryOutput {
+        let ordered: Vec<&Receipt> = self.state_cache.receipts().collect();
+        let fresh = self.freshness.fresh(&ordered, crate::now_unix(), |p, r| self.covers(p, r));
+        query.evaluate_with(fresh, |p, r| self.covers(p, r))
+    }
+
+    /// Answer a `MEM:QUERY` from the brain itself; stale receipts are left
+    /// out. Only invalidating receipts recorded since the oldest match are
+    /// fetched, but from every trace unless the query names one.
+    pub async fn query_brain(&self, query: &MemQuery) -> Result<QueryOutput, Error> {
+        let selection = query.selection();
+        let mut receipts = self.backend.query(&self.backend_selection(&selection)).await?;
+        receipts.retain(|r| selection.matches_with(r, |p, r| self.covers(p, r)));
+
+        // Whatever could have made the selection stale
+        if let Some(oldest) = receipts.iter().map(|r| r.timestamp).min() {
+            let invalidators = std::iter::once(INVALIDATE_PREFIX.trim_end_matches(':'))
+                .chain(self.freshness.rules.iter().flat_map(|r| r.invalidated_by.iter().map(String::as_str)));
+            for pattern in invalidators {
+                let related = MemQuery {
+                    filters: crate::query::QueryFilters {
+                        trace_id: selection.filters.trace_id.clone(),
+                        since: Some(oldest),
+                        ..Default::default()
+                    },
+                    ..MemQuery::pattern(pattern)
+                };
+                for receipt in self.backend.query(&self.backend_selection(&related)).await? {
+                    if !receipts.iter().any(|r| r.receipt_id == receipt.receipt_id) {
+                        receipts.push(receipt);
+                    }
+                }
+            }
+        }
+        receipts.sort_by_key(|r| r.timestamp);
+
+        let ordered: Vec<&Receipt> = receipts.iter().collect();
+        let fresh = self.freshness.fresh(&ordered, crate::now_unix(), |p, r| self.covers(p, r));
+        Ok(query.evaluate_with(fresh, |p, r| self.covers(p, r)))
+    }
+
+    /// What to ask the backend for so that no receipt `covers` would match
+    /// is missed. Backends match the stored operation only, so a pattern
+    /// that could match a `0x600:` key instead fetches everything the
+    /// filters allow.
+    fn backend_selection(&self, query: &MemQuery) -> MemQuery {
+        let keyed = query.pattern.starts_with(['*', '?']) || query.pattern.starts_with("0x");
+        MemQuery {
+            pattern: if keyed { "*".to_string() } else { query.pattern.clone() },
+            ..query.selection()
+        }
+    }
+
+    /// Enforce pre-execution check; only a successful receipt blocks, so
+    /// failed operations may be retried
+    pub fn enforce_redundancy_check(&self, operation: &str) -> Result<(), ProtocolViolation> {
+        if let Some(receipt) = self.check_prior_work(operation) {
+            Err(ProtocolViolation::new(
+                ViolationSeverity::Error,
+                "NO_REDUNDANCY",
+                format!(
+                    "Operation '{}' already completed. Receipt: {}. Do not re-execute.",
+                    operation, receipt.receipt_id
+                ),
+                0,
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    // ========================================================================
+    // MESSAGE PROCESSING
+    // ========================================================================
+
+    /// Main entry point: process outgoing message
+    pub async fn process_outgoing(&mut self, message: &str) -> Result<String, Vec<ProtocolViolation>> {
+        let evaluation = self.evaluate_outgoing(message).await;
+
+        match evaluation.optimized {
+            Some(optimized) => Ok(optimized),
+            None => Err(evaluation.violations),
+        }
+    }
+
+    /// Process outgoing message, keeping violations even when it is allowed through
+    pub async fn evaluate_outgoing(&mut self, message: &str) -> Evaluation {
+        let mut all_violations = Vec::new();
+
+        // Enforce bootstrap on first message
+        if self.metrics.total_messages == 0 {
+            if let Err(v) = self.enforce_bootstrap().await {
+                all_violations.extend(v);
+            }
+        }
+
+        // Enforce silence
+        if let Err(v) = self.enforce_silence(message) {
+            all_violations.extend(v);
+        }
+
+        // Enforce receipts
+        let (receipts, violations) = self.check_receipts(message).await;
+        all_violations.extend(violations);
+
+        // Update metrics
+        let claim = self.classify_claim(message, &receipts);
+        self.update_metrics(message, claim, &all_violations);
+        self.record_violations(&all_violations);
+
+        // Decision: block or warn? Printing is left to the caller
+        if self.strict_mode && self.has_critical_violations(&all_violations) {
+            Evaluation {
+                optimized: None,
+                rewrite: None,
+                violations: all_violations,
+            }
+        } else {
+            // Warn but allow
+            let rewrite = self.rewrite(message);
+            Evaluation {
+                optimized: Some(rewrite.text.clone()),
+                rewrite: Some(rewrite),
+                violations: all_violations,
+            }
+        }
+    }
+
+    /// Optimize message automatically
+    pub(crate) fn optimize_message(&self, message: &str) -> String {
+        self.rewrite(message).text
+    }
+
+    /// Apply every enabled rule's fix, keeping the edits and diff
+    pub fn rewrite(&self, message: &str) -> Rewrite {
+        self.rules.rewrite(message)
+    }
+
+    // ========================================================================
+    // COORDINATE DETECTION
+    // ========================================================================
+
+    fn is_coordinate(&self, message: &str) -> bool {
+        rules::is_coordinate(message)
+    }
+
+    /// Every receipt the message cites, in any syntax, in order
+    fn extract_receipts(&self, message: &str) -> Vec<Citation> {
+        citation::find_all(message)
+    }
+
+    /// The receipt id a citation stands for. A hex coordinate (bare, or
+    /// quoted as an id) names an operation, not a receipt: it resolves through
+    /// the operation key to the operation's latest successful receipt in this
+    /// trace, if there is one.
+    async fn resolve_citation(&self, citation: &Citation) -> Result<Option<String>, Error> {
+        if citation.syntax != CitationSyntax::Hex && citation.receipt_id.parse::<HexCoordinate>().is_err() {
+            return Ok(Some(citation.receipt_id.clone()));
+        }
+
+        let key = self.translator.operation_key(&citation.receipt_id);
+        if let Some(receipt) = self.state_cache.latest_success(&key) {
+            return Ok(Some(receipt.receipt_id.clone()));
+        }
+        // Not bootstrapped yet, or recorded since: ask the brain
+        let trace = self.query_brain_state().await?;
+        Ok(trace
+            .into_iter()
+            .rev()
+            .find(|r| r.success && self.translator.operation_key(&r.operation) == key)
+            .map(|r| r.receipt_id))
+    }
+
+    // ========================================================================
+    // BRAIN COMMUNICATION
+    // ========================================================================
+
+    async fn query_brain_state(&self) -> Result<Vec<Receipt>, Error> {
+        self.backend.fetch_trace(&self.trace_id).await
+    }
+
+    /// Fetch a cited receipt and check its content hash, trace and signer
+    pub async fn verify_receipt(&self, receipt_id: &str) -> Result<ReceiptVerification, Error> {
+        let Some(receipt) = self.backend.fetch_receipt(receipt_id).await? else {
+            return Ok(ReceiptVerification::Missing);
+        };
+        let verification = receipt.verify(&self.trace_id);
+        if !verification.is_valid() {
+            return Ok(verification);
+        }
+        Ok(match self.keyring.as_deref().map(|k| receipt.verify_signature(k)) {
+            Some(Err(e)) => ReceiptVerification::Signature(e),
+            _ => verification,
+        })
+    }
+
+    // ========================================================================
+    // TOKEN COUNTING
+    // ========================================================================
+
+    fn count_tokens(&self, text: &str) -> usize {
+        self.tokenizer.count_tokens(text)
+    }
+
+    // ========================================================================
+    // METRICS & REPORTING
+    // ========================================================================
+
+    fn update_metrics(&mut self, message: &str, claim: Option<ClaimOutcome>, violations: &[ProtocolViolation]) {
+        let tokens = self.count_tokens(message);
+        let coordinate = self.is_coordinate(message);
+        self.metrics
+            .record_message(&self.trace_id, tokens, coordinate, claim, violations);
+    }
+
+    fn has_critical_violations(&self, violations: &[ProtocolViolation]) -> bool {
+        violations.iter()
+            .any(|v| v.severity == ViolationSeverity::Critical)
+    }
+
+    fn record_violations(&mut self, violations: &[ProtocolViolation]) {
+        self.metrics.violations.extend_from_slice(violations);
+    }
+
+    pub fn get_metrics(&self) -> &DayZeroMetrics {
+        &self.metrics
+    }
+
+    pub fn print_report(&self) {
+        println!("\n╔════════════════════════════════════════════╗");
+        println!("║       DAY ZERO COMPLIANCE REPORT          ║");
+        println!("╚════════════════════════════════════════════╝");
+        println!("  Agent: {}", self.agent_id);
+        println!("  Trace: {}", self.trace_id);
+        println!();
+        println!("  Messages:         {}", self.metrics.total_messages);
+        println!("  Tokens:           {}", self.metrics.total_tokens);
+        println!("  Avg Tokens/Msg:   {:.1}", self.metrics.average_tokens);
+        println!("  K Value:          {:.1}", self.metrics.k_value);
+        println!();
+        println!("  Coordinate Usage: {:.1}%", self.metrics.coordinate_usage * 100.0);
+        println!(
+            "  Receipt Coverage: {:.1}% ({}/{} claims)",
+            self.metrics.receipt_coverage * 100.0,
+            self.metrics.backed_claims,
+            self.metrics.completion_claims
+        );
+        println!(
+            "  Last {} msgs:     K {:.1}, coordinates {:.1}%, coverage {:.1}%, {} critical",
+            self.metrics.window.messages,
+            self.metrics.window.k_value,
+            self.metrics.window.coordinate_usage * 100.0,
+            self.metrics.window.receipt_coverage * 100.0,
+            self.metrics.window.critical_violations
+        );
+        for (trace, coverage) in &self.metrics.coverage_by_trace {
+            for claim in &coverage.unbacked {
+                println!("    {} msg {}: \"{}\" - {}", trace, claim.message, claim.claim, claim.reason);
+            }
+        }
+        println!();
+        
+        let target = if self.metrics.k_value < 20.0 {
+            "✓ K→0 ACHIEVED"
+        } else if self.metrics.k_value < 30.0 {
+            "✓ Q PROTOCOL EXPERT"
+        } else if self.metrics.k_value < 50.0 {
+            "✓ Q PROTOCOL COMPLIANT"
+        } else {
+            "❌ TRAINING REQUIRED"
+        };
+        
+        println!("  Status:           {}", target);
+        println!();
+        
+        if !self.metrics.violations.is_empty() {
+            println!("  Violations ({}):", self.metrics.violations.len());
+            for (i, v) in self.metrics.violations.iter().take(5).enumerate() {
+                println!("    {}. {}", i + 1, v);
+            }
+            if self.metrics.violations.len() > 5 {
+                println!("    ... {} more", self.metrics.violations.len() - 5);
+            }
+        }
+        println!("╚════════════════════════════════════════════╝\n");
+    }
+
+    // ========================================================================
+    // GRADUATION CHECK
+    // ========================================================================
+
+    pub fn check_graduation(&self) -> bool {
+        // Agent can graduate from day_zero when, over the last
+        // `window_size` messages (default 100):
+        // 1. K < 20 tokens/message
+        // 2. >95% coordinate usage
+        // 3. 100% receipt coverage
+        // 4. 0 critical violations
+        self.metrics.graduated()
+    }
+
+    pub fn graduation_report(&self) -> String {
+        let window = &self.metrics.window;
+        let mark = |ok: bool| if ok { "✓" } else { "❌" };
+
+        if self.check_graduation() {
+            format!(
+                "🎓 GRADUATION ACHIEVED\n\
+                 Agent {} has mastered Q Protocol.\n\
+                 Last {} messages:\n\
+                 K = {:.1} (target: <20) ✓\n\
+                 Coordinate usage: {:.1}% (target: >95%) ✓\n\
+                 Receipt coverage: 100% ✓\n\
+                 Critical violations: 0 ✓\n\
+                 \n\
+                 day_zero.rs can be deprecated for this agent.\n\
+                 Transitioning to native A2AC communication.",
+                self.agent_id,
+                window.messages,
+                window.k_value,
+                window.coordinate_usage * 100.0
+            )
+        } else {
+            format!(
+                "📚 TRAINING IN PROGRESS\n\
+                 Agent {} requires continued day_zero enforcement.\n\
+                 Last {} messages (window: {}) {}\n\
+                 K = {:.1} (target: <20) {}\n\
+                 Coordinate usage: {:.1}% (target: >95%) {}\n\
+                 Receipt coverage: {:.1}% (target: 100%) {}\n\
+                 Critical violations: {} (target: 0) {}\n",
+                self.agent_id,
+                window.messages,
+                self.metrics.window_size,
+                mark(window.messages >= self.metrics.window_size),
+                window.k_value,
+                mark(window.k_value < TARGET_K_VALUE),
+                window.coordinate_usage * 100.0,
+                mark(window.coordinate_usage > TARGET_COORDINATE_USAGE),
+                window.receipt_coverage * 100.0,
+                mark(window.receipt_coverage >= TARGET_RECEIPT_COVERAGE),
+                window.critical_violations,
+                mark(window.critical_violations == 0)
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::brain::MemoryBackend;
+    use crate::chain::ReceiptChain;
+    use crate::keyring::{AgentKey, AgentSigningKey};
+
+    #[tokio::test]
+    async fn test_verbose_response_detection() {
+        let mut dz = DayZero::new(
+            "test-agent".to_string(),
+            "trace-123".to_string(),
+            "http://brain".to_string(),
+        )
+        .with_backend(Arc::new(MemoryBackend::new()));
+
+        let bad_response = "The brain directory serves as the central knowledge \
+                            and operational hub. It contains three subdirectories: \
+                            receipts, which stores proof of every completed operation, \
+                            state, which holds the current conversation context for each \
+                            trace, and coordinates, which is the shared dictionary of \
+                            operations that agents use to coordinate with one another.";
+
+        let result = dz.process_outgoing(bad_response).await;
+
+        // Should detect violations (warn only outside strict mode)
+        assert!(result.is_ok());
+        assert!(dz.metrics.k_value > 50.0);
+        assert!(!dz.metrics.violations.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_coordinate_acceptance() {
+        let mut dz = DayZero::new(
+            "test-agent".to_string(),
+            "trace-123".to_string(),
+            "http://brain".to_string(),
+        )
+        .with_backend(Arc::new(MemoryBackend::new()));
+
+        let good_response = "◈ BRAIN:LIST";
+
+        let result = dz.process_outgoing(good_response).await;
+
+        // Should pass with minimal violations
+        assert!(result.is_ok());
+        assert!(dz.metrics.k_value < 10.0);
+    }
+
+    #[tokio::test]
+    async fn test_rewrite_preserves_case() {
+        let mut dz = DayZero::new(
+            "test-agent".to_string(),
+            "trace-123".to_string(),
+            "http://brain".to_string(),
+        )
+        .with_backend(Arc::new(MemoryBackend::new()));
+
+        let message = "Let me confirm: Probably fine. See `Cache::Get` ◈ RECEIPT:AbC";
+        let evaluation = dz.evaluate_outgoing(message).await;
+        let rewrite = evaluation.rewrite.unwrap();
+
+        assert_eq!(rewrite.text, "Confirm: Fine. See `Cache::Get` ◈ RECEIPT:AbC");
+        assert_eq!(evaluation.optimized.as_deref(), Some(rewrite.text.as_str()));
+        let rules: Vec<_> = rewrite.edits.iter().map(|e| e.rule.as_str()).collect();
+        assert_eq!(rules, ["NO_PREAMBLE", "NO_SPECULATION"]);
+        assert!(rewrite.diff().contains("+Confirm: Fine."));
+    }
+
+    #[tokio::test]
+    async fn test_graduation_window() {
+        let mut dz = DayZero::new(
+            "test-agent".to_string(),
+            "trace-123".to_string(),
+            "http://brain".to_string(),
+        )
+        .with_backend(Arc::new(MemoryBackend::new()))
+        .with_graduation_window(10);
+        let verbose = "I will now clone the repository and then let you know how it went afterwards";
+
+        // Starting out: verbose prose
+        for _ in 0..5 {
+            dz.process_outgoing(verbose).await.unwrap();
+        }
+        assert_eq!(dz.get_metrics().coordinate_usage, 0.0);
+        assert!(!dz.check_graduation());
+
+        // Improving: coordinates only, but the window still holds prose
+        for _ in 0..9 {
+            dz.process_outgoing("◈ git:clone:repo").await.unwrap();
+        }
+        assert!((dz.get_metrics().coordinate_usage - 9.0 / 14.0).abs() < 1e-9);
+        assert!((dz.get_metrics().window.coordinate_usage - 0.9).abs() < 1e-9);
+        assert!(!dz.check_graduation());
+
+        // Graduated: a full window of clean coordinates
+        dz.process_outgoing("◈ git:clone:repo").await.unwrap();
+        assert_eq!(dz.get_metrics().window.coordinate_usage, 1.0);
+        assert!(dz.check_graduation());
+        assert!(dz.graduation_report().starts_with("🎓"));
+
+        // Regressing: an unbacked completion claim is critical
+        dz.process_outgoing("Deploy done").await.unwrap();
+        assert_eq!(dz.get_metrics().window.critical_violations, 1);
+        assert!(!dz.check_graduation());
+        assert!(dz.graduation_report().contains("Critical violations: 1 (target: 0) ❌"));
+
+        // Recovered once the violation leaves the window
+        for _ in 0..10 {
+            dz.process_outgoing("◈ git:clone:repo").await.unwrap();
+        }
+        assert!(dz.check_graduation());
+        assert_eq!(dz.get_metrics().critical_violations, 1);
+    }
+
+    #[test]
+    fn test_receipts_shared_across_dialects() {
+        let org = CoordinateRegistry::from_json(r#"{ "entities": { "git": "REPO" }, "actions": { "clone": "CLON" } }"#)
+            .unwrap();
+        let mut dz = DayZero::new(
+            "test-agent".to_string(),
+            "trace-123".to_string(),
+            "http://brain".to_string(),
+        )
+        .with_backend(Arc::new(MemoryBackend::new()))
+        .with_registry(&org)
+        .unwrap();
+
+        let receipt = Receipt::success("git:clone:repo", "git-agent-001", "trace-123", "cloned");
+        let key = dz.translator.operation_key(&receipt.operation);
+        dz.state_cache.insert(key, receipt);
+
+        let hex = dz.translator().translate("◈ git:clone:repo").unwrap();
+        assert!(dz.check_prior_work(&hex).is_some());
+        assert!(dz.check_prior_work(&hex.replace(":02:", ":04:")).is_some());
+        assert!(dz.enforce_redundancy_check("◈ git:clone:repo").is_err());
+        assert!(dz.is_coordinate(&hex));
+    }
+
+    #[tokio::test]
+    async fn test_receipt_verification() {
+        let valid = Receipt::success("git:clone:repo", "git-agent-001", "trace-123", "cloned");
+        let foreign = Receipt::success("git:clone:repo", "git-agent-001", "trace-999", "cloned");
+        let mut tampered = Receipt::success("git:push:repo", "git-agent-001", "trace-123", "pushed");
+        tampered.result = Some("force-pushed".to_string());
+
+        let backend = MemoryBackend::with_receipts([valid.clone(), foreign.clone(), tampered.clone()]);
+        let dz = DayZero::new(
+            "test-agent".to_string(),
+            "trace-123".to_string(),
+            "http://brain".to_string(),
+        )
+        .with_backend(Arc::new(backend));
+
+        assert_eq!(dz.verify_receipt(&valid.receipt_id).await.unwrap(), ReceiptVerification::Valid);
+        assert_eq!(dz.verify_receipt("rcpt_made_up").await.unwrap(), ReceiptVerification::Missing);
+        assert!(matches!(
+            dz.verify_receipt(&tampered.receipt_id).await.unwrap(),
+            ReceiptVerification::HashMismatch { .. }
+        ));
+        assert!(matches!(
+            dz.verify_receipt(&foreign.receipt_id).await.unwrap(),
+            ReceiptVerification::TraceMismatch { .. }
+        ));
+
+        let message = format!("Clone complete ◈ RECEIPT:{}", valid.receipt_id);
+        assert!(dz.enforce_receipts(&message).await.is_ok());
+
+        let violations = dz.enforce_receipts("Clone complete ◈ RECEIPT:xyz").await.unwrap_err();
+        assert_eq!(violations[0].rule, rules::RECEIPT_VALIDATION);
+        assert_eq!(violations[0].severity, ViolationSeverity::Critical);
+        assert!(violations[0].message.contains("not found"));
+
+        // Every step of a multi-step message is checked, whatever the syntax
+        let message = format!(
+            "Cloned ◈ RECEIPT:{}, then pushed {{\"receiptId\": \"{}\"}}",
+            valid.receipt_id, tampered.receipt_id
+        );
+        let violations = dz.enforce_receipts(&message).await.unwrap_err();
+        assert_eq!(violations.len(), 1);
+        let span = violations[0].span.unwrap();
+        assert!(message[span.start..span.end].contains(&tampered.receipt_id));
+        assert!(violations[0].message.contains("hash check"));
+    }
+
+    #[tokio::test]
+    async fn test_violations_recorded_once() {
+        let mut dz = DayZero::new(
+            "test-agent".to_string(),
+            "trace-123".to_string(),
+            "http://brain".to_string(),
+        )
+        .with_backend(Arc::new(MemoryBackend::new()))
+        .with_strict_mode(true);
+
+        // Blocked: recorded once, whatever the caller does with the Err
+        let blocked = dz.process_outgoing("Done ◈ RECEIPT:rcpt_made_up").await.unwrap_err();
+        assert_eq!(dz.get_metrics().violations, blocked);
+
+        // Allowed with warnings: recorded once too
+        let evaluation = dz.evaluate_outgoing("Let me check, probably fine").await;
+        assert!(evaluation.optimized.is_some() && !evaluation.violations.is_empty());
+        assert_eq!(dz.get_metrics().violations.len(), blocked.len() + evaluation.violations.len());
+    }
+
+    #[tokio::test]
+    async fn test_hex_citation_resolves_to_receipt() {
+        let mut log = ReceiptChain::new("trace-123");
+        log.append(Receipt::failure("case:resolve:x", "case-agent-001", "trace-123", "locked"));
+        log.append(Receipt::success("case:resolve:x", "case-agent-001", "trace-123", "resolved"));
+        log.append(Receipt::failure("case:update:y", "case-agent-001", "trace-123", "locked"));
+        let dz = DayZero::new(
+            "test-agent".to_string(),
+            "trace-123".to_string(),
+            "http://brain".to_string(),
+        )
+        .with_backend(Arc::new(MemoryBackend::with_receipts(log.receipts().to_vec())));
+
+        let resolved = dz.translator().translate("◈ case:resolve:x").unwrap().replace(":02:", ":04:");
+        let message = format!("Case closed {}", resolved);
+
+        // Straight from the brain, then from the bootstrapped state
+        let (receipts, violations) = dz.check_receipts(&message).await;
+        assert!(violations.is_empty(), "{:?}", violations);
+        assert_eq!(receipts[0].receipt_id, log.receipts()[1].receipt_id);
+        assert_eq!(receipts[0].verified, Ok(ReceiptVerification::Valid));
+        let mut dz = dz.with_strict_mode(true);
+        dz.enforce_bootstrap().await.unwrap();
+        assert!(dz.process_outgoing(&message).await.is_ok());
+        assert!(dz.enforce_receipts(&format!(r#"{{"receiptId": "{}"}}"#, resolved)).await.is_ok());
+
+        // An operation that never succeeded has no receipt to point at
+        let updated = dz.translator().translate("◈ case:update:y").unwrap().replace(":02:", ":04:");
+        let violations = dz.enforce_receipts(&format!("Updated {}", updated)).await.unwrap_err();
+        assert_eq!(violations[0].severity, ViolationSeverity::Critical);
+        assert!(violations[0].message.contains(&updated));
+    }
+
+    #[tokio::test]
+    async fn test_receipt_signed_by_wrong_agent() {
+        let git_key = AgentSigningKey::from_bytes("git-agent-001#1", &[1; 32]);
+        let rogue_key = AgentSigningKey::from_bytes("deploy-agent-002#1", &[2; 32]);
+        let mut keyring = Keyring::new();
+        for (agent_id, key) in [("git-agent-001", &git_key), ("deploy-agent-002", &rogue_key)] {
+            keyring
+                .add(AgentKey {
+                    agent_id: agent_id.to_string(),
+                    key_id: key.key_id().to_string(),
+                    public_key: key.public_key(),
+                    valid_from: None,
+                    valid_until: None,
+                    replaced_by: None,
+                })
+                .unwrap();
+        }
+
+        let genuine = Receipt::success("git:clone:repo", "git-agent-001", "trace-123", "cloned").signed(&git_key);
+        let forged = Receipt::success("git:push:repo", "git-agent-001", "trace-123", "pushed").signed(&rogue_key);
+        let unsigned = Receipt::success("git:tag:repo", "git-agent-001", "trace-123", "tagged");
+        let unkeyed = Receipt::success("test:run:repo", "test-agent-003", "trace-123", "42 passed");
+        let backend = MemoryBackend::with_receipts([genuine.clone(), forged.clone(), unsigned.clone(), unkeyed.clone()]);
+        let dz = DayZero::new(
+            "test-agent".to_string(),
+            "trace-123".to_string(),
+            "http://brain".to_string(),
+        )
+        .with_backend(Arc::new(backend))
+        .with_keyring(Arc::new(keyring));
+
+        assert!(dz.enforce_receipts(&format!("◈ RECEIPT:{}", genuine.receipt_id)).await.is_ok());
+
+        let violations = dz
+            .enforce_receipts(&format!("◈ RECEIPT:{}", forged.receipt_id))
+            .await
+            .unwrap_err();
+        assert_eq!(violations[0].rule, rules::RECEIPT_VALIDATION);
+        assert_eq!(violations[0].severity, ViolationSeverity::Critical);
+        assert!(violations[0].message.contains("signed by deploy-agent-002"));
+
+        // Dropping the signature does not get a forgery through...
+        let violations = dz
+            .enforce_receipts(&format!("◈ RECEIPT:{}", unsigned.receipt_id))
+            .await
+            .unwrap_err();
+        assert_eq!(violations[0].severity, ViolationSeverity::Critical);
+        assert!(violations[0].message.contains("unsigned"));
+
+        // ...but agents the keyring has no key for may still go unsigned
+        assert!(dz.enforce_receipts(&format!("◈ RECEIPT:{}", unkeyed.receipt_id)).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_bootstrap_requires_intact_chain() {
+        let mut log = ReceiptChain::new("trace-123");
+        for op in ["git:clone:repo", "test:run:repo", "git:push:repo"] {
+            log.append(Receipt::success(op, "git-agent-001", "trace-123", "ok"));
+        }
+        let bootstrap = |receipts: Vec<Receipt>| async move {
+            let mut dz = DayZero::new(
+                "test-agent".to_string(),
+                "trace-123".to_string(),
+                "http://brain".to_string(),
+            )
+            .with_backend(Arc::new(MemoryBackend::with_receipts(receipts)));
+            let result = dz.enforce_bootstrap().await;
+            (result, dz.check_prior_work("git:clone:repo").is_some())
+        };
+
+        let (result, cached) = bootstrap(log.receipts().to_vec()).await;
+        assert!(result.is_ok() && cached);
+
+        let mut receipts = log.receipts().to_vec();
+        receipts.remove(1);
+        let (result, cached) = bootstrap(receipts).await;
+        let violations = result.unwrap_err();
+        assert_eq!(violations[0].severity, ViolationSeverity::Critical);
+        assert!(violations[0].message.contains("chain broken at #1"));
+        assert!(!cached);
+    }
+
+    #[tokio::test]
+    async fn test_default_backend_is_brain_url() {
+        let bootstrap = |brain_url: &str| {
+            let mut dz = DayZero::new("test-agent".to_string(), "trace-123".to_string(), brain_url.to_string());
+            async move { dz.enforce_bootstrap().await.unwrap_err().remove(0) }
+        };
+
+        // Neither a bad URL nor an unreachable brain passes for an empty one
+        let violation = bootstrap("not a url").await;
+        assert_eq!(violation.severity, ViolationSeverity::Critical);
+        assert!(violation.message.contains("invalid brain url 'not a url'"));
+        assert!(bootstrap("http://127.0.0.1:9").await.message.contains("cannot reach"));
+    }
+
+    #[tokio::test]
+    async fn test_redundancy_allows_retries() {
+        let mut log = ReceiptChain::new("trace-123");
+        log.append(Receipt::failure("git:push:repo", "git-agent-001", "trace-123", "rejected"));
+        log.append(Receipt::success("git:push:repo", "git-agent-001", "trace-123", "pushed"));
+        log.append(Receipt::failure("git:push:repo", "git-agent-001", "trace-123", "timeout"));
+        log.append(Receipt::failure("deploy:prod:app", "git-agent-001", "trace-123", "timeout"));
+
+        let mut dz = DayZero::new(
+            "test-agent".to_string(),
+            "trace-123".to_string(),
+            "http://brain".to_string(),
+        )
+        .with_backend(Arc::new(MemoryBackend::with_receipts(log.receipts().to_vec())));
+        dz.enforce_bootstrap().await.unwrap();
+
+        // Neither the earlier nor the later failure hides the push
+        assert_eq!(dz.receipt_history("git:push:repo").len(), 3);
+        assert_eq!(dz.check_prior_work("git:push:repo"), Some(&log.receipts()[1]));
+        assert_eq!(dz.latest_attempt("git:push:repo"), Some(&log.receipts()[2]));
+        let violation = dz.enforce_redundancy_check("git:push:repo").unwrap_err();
+        assert!(violation.message.contains(&log.receipts()[1].receipt_id));
+
+        // Only failures so far: retrying is allowed
+        assert!(dz.latest_attempt("deploy:prod:app").is_some());
+        assert!(dz.enforce_redundancy_check("deploy:prod:app").is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_stale_receipts_allow_reexecution() {
+        let aged = |op: &str, age: u64| {
+            Receipt {
+                timestamp: crate::now_unix() - age,
+                ..Receipt::success(op, "git-agent-001", "trace-123", "ok")
+            }
+            .sealed()
+        };
+        let mut log = ReceiptChain::new("trace-123");
+        log.append(aged("git:pull:origin", 1000));
+        log.append(aged("report:generate:weekly", 10));
+        log.append(aged("build:run:app", 10));
+        log.append(Receipt::invalidating("report:*", "git-agent-001", "trace-123"));
+
+        let mut dz = DayZero::new(
+            "test-agent".to_string(),
+            "trace-123".to_string(),
+            "http://brain".to_string(),
+        )
+        .with_backend(Arc::new(MemoryBackend::with_receipts(log.receipts().to_vec())));
+        dz.enforce_bootstrap().await.unwrap();
+
+        let pull = &log.receipts()[0];
+        assert!(matches!(dz.staleness(pull), Some(Staleness::Expired { max_age: 300, .. })));
+        assert!(dz.enforce_redundancy_check("git:pull:origin").is_ok());
+        assert!(matches!(dz.staleness(&log.receipts()[1]), Some(Staleness::Invalidated { .. })));
+        assert!(dz.enforce_redundancy_check("report:generate:weekly").is_ok());
+        assert!(dz.enforce_redundancy_check("build:run:app").is_err());
+
+        let reports: MemQuery = "report|count".parse().unwrap();
+        assert_eq!(dz.query_state(&reports), QueryOutput::Count { count: 0, holds: None });
+        assert_eq!(dz.query_brain(&reports).await.unwrap(), QueryOutput::Count { count: 0, holds: None });
+        let builds: MemQuery = "build|count".parse().unwrap();
+        assert!(dz.query_brain(&builds).await.unwrap().is_truthy());
+
+        // Without freshness rules only the explicit invalidation counts
+        let dz = dz.with_freshness(FreshnessPolicy::default());
+        assert!(dz.enforce_redundancy_check("git:pull:origin").is_err());
+        assert!(dz.enforce_redundancy_check("report:generate:weekly").is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_mem_query() {
+        let mut log = ReceiptChain::new("trace-123");
+        log.append(Receipt::success("research:paper:a", "research-agent", "trace-123", "ok"));
+        log.append(Receipt::failure("research:paper:b", "research-agent", "trace-123", "timeout"));
+        log.append(Receipt::success("research:paper:c", "research-agent", "trace-123", "ok"));
+        log.append(Receipt::success("case:resolve:ticket-1", "case-agent", "trace-123", "ok"));
+        let backend = MemoryBackend::with_receipts(log.receipts().to_vec());
+        backend
+            .store(Receipt::success("research:paper:d", "research-agent", "trace-999", "ok"))
+            .await
+            .unwrap();
+
+        let mut dz = DayZero::new(
+            "test-agent".to_string(),
+            "trace-123".to_string(),
+            "http://brain".to_string(),
+        )
+        .with_backend(Arc::new(backend));
+        dz.enforce_bootstrap().await.unwrap();
+
+        let batch: MemQuery = "MEM:QUERY:research|count(receipts)==3".parse().unwrap();
+        assert!(dz.query_state(&batch).is_truthy());
+        assert_eq!(
+            dz.query_brain(&batch).await.unwrap(),
+            QueryOutput::Count { count: 4, holds: Some(false) }
+        );
+
+        let failed: MemQuery = "research:*[trace=trace-123]|any_failed".parse().unwrap();
+        assert!(dz.query_state(&failed).is_truthy());
+        assert!(dz.query_brain(&failed).await.unwrap().is_truthy());
+
+        // Hex patterns match semantic operations through the translator key
+        let resolved: MemQuery = "0x600:02:CASE:RSLV:*|count".parse().unwrap();
+        let one = QueryOutput::Count { count: 1, holds: None };
+        assert_eq!(dz.query_state(&resolved), one);
+        assert_eq!(dz.query_brain(&resolved).await.unwrap(), one);
+    }
+
+    #[tokio::test]
+    async fn test_receipt_coverage() {
+        let clone = Receipt::success("git:clone:repo", "git-agent-001", "trace-123", "cloned");
+        let mut dz = DayZero::new(
+            "test-agent".to_string(),
+            "trace-123".to_string(),
+            "http://brain".to_string(),
+        )
+        .with_backend(Arc::new(MemoryBackend::with_receipts([clone.clone()])));
+
+        dz.process_outgoing("◈ git:status:repo").await.unwrap();
+        assert_eq!(dz.get_metrics().completion_claims, 0);
+        assert_eq!(dz.get_metrics().receipt_coverage, 1.0);
+
+        dz.process_outgoing(&format!("Clone done ◈ RECEIPT:{}", clone.receipt_id)).await.unwrap();
+        dz.process_outgoing("Deploy finished").await.unwrap();
+        // A citation with no claim phrase is not a completion claim
+        dz.process_outgoing("◈ RECEIPT:rcpt_made_up").await.unwrap();
+        assert_eq!(dz.get_metrics().completion_claims, 2);
+        dz.process_outgoing("Deploy done ◈ RECEIPT:rcpt_made_up").await.unwrap();
+
+        let metrics = dz.get_metrics();
+        assert_eq!((metrics.backed_claims, metrics.completion_claims), (1, 3));
+        assert!((metrics.receipt_coverage - 1.0 / 3.0).abs() < 1e-9);
+
+        let trace = &metrics.coverage_by_trace["trace-123"];
+        assert_eq!(trace.unbacked.len(), 2);
+        assert_eq!((trace.unbacked[0].message, trace.unbacked[0].claim.as_str()), (3, "finished"));
+        assert_eq!(trace.unbacked[0].reason, "no receipt cited");
+        assert_eq!(trace.unbacked[1].message, 5);
+        assert!(trace.unbacked[1].reason.contains("rcpt_made_up not found"));
+
+        // Claims are whatever the configured rule counts as one
+        dz.rules_mut()
+            .get_mut::<ReceiptsAreTruthRule>(rules::RECEIPTS_ARE_TRUTH)
+            .unwrap()
+            .set_claims(vec!["shipped".to_string()]);
+        dz.process_outgoing("Deploy finished").await.unwrap();
+        dz.process_outgoing("Deploy shipped").await.unwrap();
+        let trace = &dz.get_metrics().coverage_by_trace["trace-123"];
+        assert_eq!(trace.unbacked.len(), 3);
+        assert_eq!((trace.unbacked[2].message, trace.unbacked[2].claim.as_str()), (7, "shipped"));
+    }
+}
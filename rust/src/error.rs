@@ -0,0 +1,56 @@
+// error.rs
+// Crate-wide error type
+
+use thiserror::Error;
+
+/// Errors surfaced by the q-protocol runtime
+#[derive(Debug, Error)]
+pub enum Error {
+    /// Brain query or store failed
+    #[error("brain error: {0}")]
+    Brain(#[from] crate::brain::BrainError),
+
+    /// Receipt could not be found
+    #[error("receipt not found: {0}")]
+    ReceiptNotFound(String),
+
+    /// Malformed A2AC(md) expression
+    #[error("parse error: {0}")]
+    Parse(#[from] crate::a2ac::ParseError),
+
+    /// Malformed or forged proto `AgentReceipt`
+    #[error("agent receipt error: {0}")]
+    AgentReceipt(#[from] crate::agent_receipt::AgentReceiptError),
+
+    /// Malformed 0x600 hex coordinate
+    #[error("hex coordinate error: {0}")]
+    HexCoordinate(#[from] crate::hex_coordinate::HexCoordinateError),
+
+    /// Semantic ⇄ hex coordinate translation failed
+    #[error("translate error: {0}")]
+    Translate(#[from] crate::translate::TranslateError),
+
+    /// Malformed MEM:QUERY pattern
+    #[error("query error: {0}")]
+    Query(#[from] crate::query::QueryError),
+
+    /// Invalid enforcement policy
+    #[error("policy error: {0}")]
+    Policy(#[from] crate::policy::PolicyError),
+
+    /// Unreadable keyring or malformed key
+    #[error("keyring error: {0}")]
+    Keyring(#[from] crate::keyring::KeyringError),
+
+    /// Unusable tokenizer vocabulary or model
+    #[error("tokenizer error: {0}")]
+    Tokenizer(#[from] crate::tokenizer::TokenizerError),
+
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("json error: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
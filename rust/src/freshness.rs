@@ -0,0 +1,187 @@
+// freshness.rs
+// When a receipt stops counting as prior work
+//
+// A receipt is stale when
+//   - a freshness rule matching its operation sets `max_age` and the receipt
+//     is older than that,
+//   - a rule's `invalidated_by` pattern matches a receipt recorded after it
+//     (a `git:push` makes an earlier `git:pull` stale), or
+//   - a later, successful invalidation receipt `cache:invalidate:<pattern>`
+//     matches its operation.
+//
+// Patterns are MEM:QUERY operation patterns (glob, or segment prefix). Rules
+// come from the policy's [[freshness]] tables:
+//
+//   [[freshness]]
+//   pattern = "git:pull"
+//   max_age = 300                  # seconds, optional
+//   invalidated_by = ["git:push"]  # optional
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::query::matches_operation;
+use crate::receipt::Receipt;
+
+/// Operation prefix of an explicit invalidation receipt; the rest is the
+/// pattern of operations it invalidates
+pub const INVALIDATE_PREFIX: &str = "cache:invalidate:";
+
+/// Freshness for the operations matching `pattern`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FreshnessRule {
+    pub pattern: String,
+    /// Seconds a receipt stays valid
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_age: Option<u64>,
+    /// Later receipts for these operations make it stale
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub invalidated_by: Vec<String>,
+}
+
+/// Why a receipt no longer counts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Staleness {
+    Expired { age: u64, max_age: u64 },
+    Superseded { receipt_id: String, operation: String },
+    Invalidated { receipt_id: String },
+}
+
+impl fmt::Display for Staleness {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Staleness::Expired { age, max_age } => write!(f, "expired ({}s old, max {}s)", age, max_age),
+            Staleness::Superseded { receipt_id, operation } => {
+                write!(f, "superseded by {} ({})", receipt_id, operation)
+            }
+            Staleness::Invalidated { receipt_id } => write!(f, "invalidated by {}", receipt_id),
+        }
+    }
+}
+
+/// The freshness rules in force
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FreshnessPolicy {
+    pub rules: Vec<FreshnessRule>,
+}
+
+impl FreshnessPolicy {
+    pub fn new(rules: Vec<FreshnessRule>) -> Self {
+        FreshnessPolicy { rules }
+    }
+
+    /// The rules in the bundled policy.toml
+    pub fn builtin() -> Self {
+        let rule = |pattern: &str, max_age: u64, invalidated_by: &[&str]| FreshnessRule {
+            pattern: pattern.to_string(),
+            max_age: Some(max_age),
+            invalidated_by: invalidated_by.iter().map(|p| p.to_string()).collect(),
+        };
+        FreshnessPolicy::new(vec![
+            rule("git:pull", 300, &["git:push", "git:commit"]),
+            rule("report:generate", 3600, &[]),
+            rule("0x600:??:CASE:READ:*", 60, &["0x600:??:CASE:UPDT:*", "0x600:??:CASE:RSLV:*"]),
+        ])
+    }
+
+    /// Why `receipt` is stale at `now`, given the receipts recorded after it;
+    /// `matches(pattern, receipt)` decides whether a pattern covers a receipt
+    pub fn staleness<'a>(
+        &self,
+        receipt: &Receipt,
+        later: impl IntoIterator<Item = &'a Receipt>,
+        now: u64,
+        matches: impl Fn(&str, &Receipt) -> bool,
+    ) -> Option<Staleness> {
+        let rules: Vec<&FreshnessRule> = self.rules.iter().filter(|r| matches(&r.pattern, receipt)).collect();
+
+        let age = now.saturating_sub(receipt.timestamp);
+        if let Some(max_age) = rules.iter().filter_map(|r| r.max_age).min() {
+            if age > max_age {
+                return Some(Staleness::Expired { age, max_age });
+            }
+        }
+
+        for next in later {
+            if let Some(target) = next.operation.strip_prefix(INVALIDATE_PREFIX) {
+                if next.success && matches(target, receipt) {
+                    return Some(Staleness::Invalidated {
+                        receipt_id: next.receipt_id.clone(),
+                    });
+                }
+                continue;
+            }
+            if rules.iter().flat_map(|r| &r.invalidated_by).any(|p| matches(p, next)) {
+                return Some(Staleness::Superseded {
+                    receipt_id: next.receipt_id.clone(),
+                    operation: next.operation.clone(),
+                });
+            }
+        }
+        None
+    }
+
+    /// `staleness`, matching patterns against the receipt's operation as written
+    pub fn check<'a>(
+        &self,
+        receipt: &Receipt,
+        later: impl IntoIterator<Item = &'a Receipt>,
+        now: u64,
+    ) -> Option<Staleness> {
+        self.staleness(receipt, later, now, |pattern, r| matches_operation(pattern, &r.operation))
+    }
+
+    /// The receipts of `ordered` (oldest first) that are still fresh at `now`
+    pub fn fresh<'a>(&self, ordered: &[&'a Receipt], now: u64, matches: impl Fn(&str, &Receipt) -> bool) -> Vec<&'a Receipt> {
+        ordered
+            .iter()
+            .enumerate()
+            .filter(|(i, r)| {
+                self.staleness(r, ordered[i + 1..].iter().copied(), now, &matches)
+                    .is_none()
+            })
+            .map(|(_, r)| *r)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(op: &str, timestamp: u64) -> Receipt {
+        Receipt {
+            timestamp,
+            ..Receipt::new(op, "git-agent-001", "trace_abc", true)
+        }
+        .sealed()
+    }
+
+    #[test]
+    fn test_staleness() {
+        let policy = FreshnessPolicy::builtin();
+        let pull = at("git:pull:origin", 1000);
+
+        assert_eq!(policy.check(&pull, [], 1200), None);
+        assert_eq!(policy.check(&pull, [], 1400), Some(Staleness::Expired { age: 400, max_age: 300 }));
+
+        let push = at("git:push:origin", 1100);
+        assert!(matches!(policy.check(&pull, [&push], 1200), Some(Staleness::Superseded { .. })));
+        assert_eq!(policy.check(&push, [&pull], 1200), None);
+
+        // Operations with no rule only go stale when explicitly invalidated
+        let build = at("build:run:app", 0);
+        assert_eq!(policy.check(&build, [&push], u64::MAX), None);
+        let invalidate = Receipt::invalidating("build:*", "git-agent-001", "trace_abc");
+        assert_eq!(
+            policy.check(&build, [&invalidate], 1200),
+            Some(Staleness::Invalidated { receipt_id: invalidate.receipt_id.clone() })
+        );
+
+        let read = at("0x600:01:CASE:READ:a1b2c3", 1000);
+        let resolve = at("0x600:02:CASE:RSLV:a1b2c3", 1010);
+        let ordered = [&read, &resolve];
+        assert_eq!(policy.fresh(&ordered, 1020, |p, r| matches_operation(p, &r.operation)), [&resolve]);
+    }
+}
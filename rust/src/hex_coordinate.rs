@@ -0,0 +1,309 @@
+// hex_coordinate.rs
+// The 0x600 hex coordinate address format (README "Coordinate Structure",
+// agent_voxel.proto `HexCoordinate`)
+//
+//   0x600 : 02 : CASE : RSLV : a1b2c3
+//   base    space entity action state hash
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// Protocol identifier every hex coordinate starts with
+pub const BASE_ADDRESS: &str = "0x600";
+
+/// Entity and action codes are exactly this many characters
+pub const CODE_LENGTH: usize = 4;
+
+/// Accepted state hash lengths: the README examples use 6, the spec 8
+pub const STATE_HASH_LENGTHS: [usize; 2] = [6, 8];
+
+/// Entity codes from the README table that fit the 4-char rule
+pub const KNOWN_ENTITY_CODES: [&str; 6] = ["CASE", "LEAD", "ACCT", "KNOW", "FLOW", "MODL"];
+
+/// Action codes from the README table
+pub const KNOWN_ACTION_CODES: [&str; 8] =
+    ["READ", "CREA", "UPDT", "DELE", "RSLV", "ESCL", "INFE", "SYNC"];
+
+// ============================================================================
+// SPACE CODES
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SpaceCode {
+    Intent,  // 01: What the agent wants to do
+    Action,  // 02: What the agent is doing
+    State,   // 03: Current execution state
+    Receipt, // 04: Proof of completed execution
+    Error,   // FF: Error conditions
+}
+
+impl SpaceCode {
+    pub fn code(&self) -> &'static str {
+        match self {
+            SpaceCode::Intent => "01",
+            SpaceCode::Action => "02",
+            SpaceCode::State => "03",
+            SpaceCode::Receipt => "04",
+            SpaceCode::Error => "FF",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SpaceCode::Intent => "INTENT",
+            SpaceCode::Action => "ACTION",
+            SpaceCode::State => "STATE",
+            SpaceCode::Receipt => "RECEIPT",
+            SpaceCode::Error => "ERROR",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "01" => Some(SpaceCode::Intent),
+            "02" => Some(SpaceCode::Action),
+            "03" => Some(SpaceCode::State),
+            "04" => Some(SpaceCode::Receipt),
+            "FF" | "ff" => Some(SpaceCode::Error),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for SpaceCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+// ============================================================================
+// ERRORS
+// ============================================================================
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum HexCoordinateError {
+    #[error("expected 5 ':'-separated fields, got {0}")]
+    FieldCount(usize),
+
+    #[error("base address must be {BASE_ADDRESS}, got '{0}'")]
+    BaseAddress(String),
+
+    #[error("unknown space code '{0}' (expected 01, 02, 03, 04 or FF)")]
+    UnknownSpaceCode(String),
+
+    #[error("{field} code '{code}' must be exactly {CODE_LENGTH} characters (got {len})")]
+    CodeLength {
+        field: &'static str,
+        code: String,
+        len: usize,
+    },
+
+    #[error("{field} code '{code}' must be uppercase A-Z or 0-9")]
+    InvalidCode { field: &'static str, code: String },
+
+    #[error("state hash '{hash}' must be 6 or 8 hex characters (got {len})")]
+    StateHashLength { hash: String, len: usize },
+
+    #[error("state hash '{0}' contains non-hex characters")]
+    InvalidStateHash(String),
+}
+
+// ============================================================================
+// HEX COORDINATE
+// ============================================================================
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct HexCoordinate {
+    space: SpaceCode,
+    entity: String,
+    action: String,
+    state_hash: String,
+}
+
+impl HexCoordinate {
+    /// Build a coordinate, validating every field
+    pub fn new(
+        space: SpaceCode,
+        entity: &str,
+        action: &str,
+        state_hash: &str,
+    ) -> Result<Self, HexCoordinateError> {
+        validate_code("entity", entity)?;
+        validate_code("action", action)?;
+        validate_state_hash(state_hash)?;
+
+        Ok(HexCoordinate {
+            space,
+            entity: entity.to_string(),
+            action: action.to_string(),
+            state_hash: state_hash.to_string(),
+        })
+    }
+
+    pub fn space(&self) -> SpaceCode {
+        self.space
+    }
+
+    pub fn entity(&self) -> &str {
+        &self.entity
+    }
+
+    pub fn action(&self) -> &str {
+        &self.action
+    }
+
+    pub fn state_hash(&self) -> &str {
+        &self.state_hash
+    }
+
+    /// Same entity/action/hash in another space (e.g. ACTION → RECEIPT)
+    pub fn in_space(&self, space: SpaceCode) -> Self {
+        HexCoordinate {
+            space,
+            ..self.clone()
+        }
+    }
+
+    pub fn is_known_entity(&self) -> bool {
+        KNOWN_ENTITY_CODES.contains(&self.entity.as_str())
+    }
+
+    pub fn is_known_action(&self) -> bool {
+        KNOWN_ACTION_CODES.contains(&self.action.as_str())
+    }
+}
+
+impl FromStr for HexCoordinate {
+    type Err = HexCoordinateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields: Vec<&str> = s.trim().split(':').collect();
+        let [base, space, entity, action, state_hash] = fields[..] else {
+            return Err(HexCoordinateError::FieldCount(fields.len()));
+        };
+
+        if !base.eq_ignore_ascii_case(BASE_ADDRESS) {
+            return Err(HexCoordinateError::BaseAddress(base.to_string()));
+        }
+
+        let space = SpaceCode::from_code(space)
+            .ok_or_else(|| HexCoordinateError::UnknownSpaceCode(space.to_string()))?;
+
+        HexCoordinate::new(space, entity, action, state_hash)
+    }
+}
+
+impl fmt::Display for HexCoordinate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}:{}:{}",
+            BASE_ADDRESS, self.space, self.entity, self.action, self.state_hash
+        )
+    }
+}
+
+impl TryFrom<String> for HexCoordinate {
+    type Error = HexCoordinateError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<HexCoordinate> for String {
+    fn from(coord: HexCoordinate) -> Self {
+        coord.to_string()
+    }
+}
+
+/// Check an entity or action code against the 4-char rule
+pub fn validate_code(field: &'static str, code: &str) -> Result<(), HexCoordinateError> {
+    let len = code.chars().count();
+    if len != CODE_LENGTH {
+        return Err(HexCoordinateError::CodeLength {
+            field,
+            code: code.to_string(),
+            len,
+        });
+    }
+    if !code.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit()) {
+        return Err(HexCoordinateError::InvalidCode {
+            field,
+            code: code.to_string(),
+        });
+    }
+    Ok(())
+}
+
+fn validate_state_hash(hash: &str) -> Result<(), HexCoordinateError> {
+    let len = hash.chars().count();
+    if !STATE_HASH_LENGTHS.contains(&len) {
+        return Err(HexCoordinateError::StateHashLength {
+            hash: hash.to_string(),
+            len,
+        });
+    }
+    if !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(HexCoordinateError::InvalidStateHash(hash.to_string()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_readme_roundtrip() {
+        for example in ["0x600:02:CASE:RSLV:a1b2c3", "0x600:04:CASE:RSLV:a1b2c3"] {
+            let coord: HexCoordinate = example.parse().unwrap();
+            assert_eq!(coord.to_string(), example);
+            assert_eq!(coord.entity(), "CASE");
+            assert_eq!(coord.action(), "RSLV");
+            assert!(coord.is_known_entity() && coord.is_known_action());
+        }
+
+        let coord: HexCoordinate = "0x600:FF:MODL:INFE:0badc0de".parse().unwrap();
+        assert_eq!(coord.space(), SpaceCode::Error);
+        assert_eq!(coord.in_space(SpaceCode::Receipt).to_string(), "0x600:04:MODL:INFE:0badc0de");
+
+        let json = serde_json::to_string(&coord).unwrap();
+        assert_eq!(json, "\"0x600:FF:MODL:INFE:0badc0de\"");
+        assert_eq!(serde_json::from_str::<HexCoordinate>(&json).unwrap(), coord);
+    }
+
+    #[test]
+    fn test_validation_errors() {
+        let err = |s: &str| s.parse::<HexCoordinate>().unwrap_err();
+
+        assert_eq!(err("0x600:02:CASE:RSLV"), HexCoordinateError::FieldCount(4));
+        assert_eq!(err("0x700:02:CASE:RSLV:a1b2c3"), HexCoordinateError::BaseAddress("0x700".into()));
+        assert_eq!(err("0x600:05:CASE:RSLV:a1b2c3"), HexCoordinateError::UnknownSpaceCode("05".into()));
+        assert_eq!(
+            err("0x600:02:VOXEL:SYNC:a1b2c3"),
+            HexCoordinateError::CodeLength { field: "entity", code: "VOXEL".into(), len: 5 }
+        );
+        assert_eq!(
+            err("0x600:02:CASE:RESOLVE:a1b2c3"),
+            HexCoordinateError::CodeLength { field: "action", code: "RESOLVE".into(), len: 7 }
+        );
+        assert_eq!(
+            err("0x600:02:case:RSLV:a1b2c3"),
+            HexCoordinateError::InvalidCode { field: "entity", code: "case".into() }
+        );
+        assert_eq!(
+            err("0x600:02:CASE:RSLV:a1b2"),
+            HexCoordinateError::StateHashLength { hash: "a1b2".into(), len: 4 }
+        );
+        assert_eq!(err("0x600:02:CASE:RSLV:a1b2zz"), HexCoordinateError::InvalidStateHash("a1b2zz".into()));
+
+        assert_eq!(
+            err("0x600:02:VOXEL:SYNC:a1b2c3").to_string(),
+            "entity code 'VOXEL' must be exactly 4 characters (got 5)"
+        );
+    }
+}
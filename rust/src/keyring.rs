@@ -0,0 +1,336 @@
+// keyring.rs
+// Ed25519 receipt signatures and the agent keyring that checks authorship
+//
+// A signature covers a receipt's content hash, so it also covers every field
+// that hash covers. Once the keyring holds a key for an agent, that agent's
+// receipts must be signed: dropping the signature is not a way around it.
+// Keyring file (TOML), one entry per key:
+//
+//   [[key]]
+//   agent_id = "git-agent-001"
+//   key_id = "git-agent-001#2026-01"
+//   public_key = "<base64, 32 bytes>"
+//   valid_from = 1767225600          # optional, unix seconds
+//   valid_until = 1775001600         # optional: set when the key is rotated out
+//   replaced_by = "git-agent-001#2026-04"
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum KeyringError {
+    #[error("invalid keyring: {0}")]
+    Toml(#[from] toml::de::Error),
+
+    #[error("key '{key_id}': {reason}")]
+    InvalidKey { key_id: String, reason: String },
+
+    #[error("duplicate key id '{0}'")]
+    DuplicateKey(String),
+
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Why a receipt signature was not accepted
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum SignatureError {
+    #[error("signed with unknown key '{0}'")]
+    UnknownKey(String),
+
+    #[error("signed by {signer} (key '{key_id}'), not {agent_id}")]
+    WrongAgent {
+        agent_id: String,
+        signer: String,
+        key_id: String,
+    },
+
+    #[error("key '{key_id}' was not valid at {timestamp}")]
+    KeyNotValid { key_id: String, timestamp: u64 },
+
+    #[error("signature does not verify under key '{0}'")]
+    Invalid(String),
+
+    #[error("unsigned, but the keyring has keys for {0}")]
+    Unsigned(String),
+}
+
+// ============================================================================
+// SIGNATURES
+// ============================================================================
+
+/// Detached Ed25519 signature over a receipt's content hash
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReceiptSignature {
+    pub key_id: String,
+    /// Base64, 64 bytes
+    pub signature: String,
+}
+
+/// An agent's private key
+pub struct AgentSigningKey {
+    key_id: String,
+    key: SigningKey,
+}
+
+impl AgentSigningKey {
+    pub fn from_bytes(key_id: &str, secret: &[u8; 32]) -> Self {
+        AgentSigningKey {
+            key_id: key_id.to_string(),
+            key: SigningKey::from_bytes(secret),
+        }
+    }
+
+    /// Key from a base64 32-byte secret
+    pub fn from_base64(key_id: &str, secret: &str) -> Result<Self, KeyringError> {
+        let secret: [u8; 32] = decode_fixed(key_id, secret.trim())?;
+        Ok(Self::from_bytes(key_id, &secret))
+    }
+
+    pub fn key_id(&self) -> &str {
+        &self.key_id
+    }
+
+    /// Base64 public key, as written in a keyring file
+    pub fn public_key(&self) -> String {
+        BASE64.encode(self.key.verifying_key().as_bytes())
+    }
+
+    pub fn sign(&self, content_hash: &str) -> ReceiptSignature {
+        ReceiptSignature {
+            key_id: self.key_id.clone(),
+            signature: BASE64.encode(self.key.sign(content_hash.as_bytes()).to_bytes()),
+        }
+    }
+}
+
+impl std::fmt::Debug for AgentSigningKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AgentSigningKey").field("key_id", &self.key_id).finish_non_exhaustive()
+    }
+}
+
+fn decode_fixed<const N: usize>(key_id: &str, encoded: &str) -> Result<[u8; N], KeyringError> {
+    let invalid = |reason: String| KeyringError::InvalidKey {
+        key_id: key_id.to_string(),
+        reason,
+    };
+    let bytes = BASE64.decode(encoded).map_err(|e| invalid(e.to_string()))?;
+    let len = bytes.len();
+    bytes
+        .try_into()
+        .map_err(|_| invalid(format!("expected {} bytes, got {}", N, len)))
+}
+
+// ============================================================================
+// KEYRING
+// ============================================================================
+
+/// A public key and its rotation window
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AgentKey {
+    pub agent_id: String,
+    pub key_id: String,
+    pub public_key: String,
+    #[serde(default)]
+    pub valid_from: Option<u64>,
+    #[serde(default)]
+    pub valid_until: Option<u64>,
+    #[serde(default)]
+    pub replaced_by: Option<String>,
+}
+
+impl AgentKey {
+    /// Whether a receipt stamped at `timestamp` may use this key
+    pub fn is_valid_at(&self, timestamp: u64) -> bool {
+        self.valid_from.is_none_or(|from| timestamp >= from)
+            && self.valid_until.is_none_or(|until| timestamp < until)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct KeyringFile {
+    #[serde(default, rename = "key")]
+    keys: Vec<AgentKey>,
+}
+
+/// Public keys by agent, for checking who signed a receipt
+#[derive(Debug, Clone, Default)]
+pub struct Keyring {
+    keys: Vec<(AgentKey, VerifyingKey)>,
+}
+
+impl Keyring {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_toml(toml: &str) -> Result<Self, KeyringError> {
+        let file: KeyringFile = toml::from_str(toml)?;
+        let mut keyring = Keyring::new();
+        for key in file.keys {
+            keyring.add(key)?;
+        }
+        Ok(keyring)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, KeyringError> {
+        Self::from_toml(&std::fs::read_to_string(path)?)
+    }
+
+    /// Add a key; key ids must be unique
+    pub fn add(&mut self, key: AgentKey) -> Result<(), KeyringError> {
+        if self.get(&key.key_id).is_some() {
+            return Err(KeyringError::DuplicateKey(key.key_id));
+        }
+        let bytes: [u8; 32] = decode_fixed(&key.key_id, &key.public_key)?;
+        let verifying = VerifyingKey::from_bytes(&bytes).map_err(|e| KeyringError::InvalidKey {
+            key_id: key.key_id.clone(),
+            reason: e.to_string(),
+        })?;
+        self.keys.push((key, verifying));
+        Ok(())
+    }
+
+    pub fn get(&self, key_id: &str) -> Option<&AgentKey> {
+        self.keys.iter().map(|(k, _)| k).find(|k| k.key_id == key_id)
+    }
+
+    /// Every key ever issued to `agent_id`, in file order
+    pub fn keys_for<'a>(&'a self, agent_id: &'a str) -> impl Iterator<Item = &'a AgentKey> {
+        self.keys.iter().map(|(k, _)| k).filter(move |k| k.agent_id == agent_id)
+    }
+
+    /// Whether receipts from `agent_id` must be signed: true once the
+    /// keyring holds any key for it
+    pub fn requires_signature(&self, agent_id: &str) -> bool {
+        self.keys_for(agent_id).next().is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Check that `signature` over `content_hash` was made by `agent_id`
+    /// with a key valid at `timestamp`
+    pub fn verify(
+        &self,
+        agent_id: &str,
+        content_hash: &str,
+        timestamp: u64,
+        signature: &ReceiptSignature,
+    ) -> Result<(), SignatureError> {
+        let (key, verifying) = self
+            .keys
+            .iter()
+            .find(|(k, _)| k.key_id == signature.key_id)
+            .ok_or_else(|| SignatureError::UnknownKey(signature.key_id.clone()))?;
+
+        if key.agent_id != agent_id {
+            return Err(SignatureError::WrongAgent {
+                agent_id: agent_id.to_string(),
+                signer: key.agent_id.clone(),
+                key_id: key.key_id.clone(),
+            });
+        }
+        if !key.is_valid_at(timestamp) {
+            return Err(SignatureError::KeyNotValid {
+                key_id: key.key_id.clone(),
+                timestamp,
+            });
+        }
+
+        let invalid = || SignatureError::Invalid(key.key_id.clone());
+        let bytes: [u8; 64] = BASE64
+            .decode(&signature.signature)
+            .ok()
+            .and_then(|b| b.try_into().ok())
+            .ok_or_else(invalid)?;
+        verifying
+            .verify(content_hash.as_bytes(), &Signature::from_bytes(&bytes))
+            .map_err(|_| invalid())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::receipt::Receipt;
+
+    fn key(seed: u8, key_id: &str) -> AgentSigningKey {
+        AgentSigningKey::from_bytes(key_id, &[seed; 32])
+    }
+
+    #[test]
+    fn test_keyring_rotation() {
+        let old = key(1, "git-agent-001#1");
+        let new = key(2, "git-agent-001#2");
+        let toml = format!(
+            r#"
+            [[key]]
+            agent_id = "git-agent-001"
+            key_id = "git-agent-001#1"
+            public_key = "{}"
+            valid_until = 2000
+            replaced_by = "git-agent-001#2"
+
+            [[key]]
+            agent_id = "git-agent-001"
+            key_id = "git-agent-001#2"
+            public_key = "{}"
+            valid_from = 2000
+            "#,
+            old.public_key(),
+            new.public_key()
+        );
+        let keyring = Keyring::from_toml(&toml).unwrap();
+        assert_eq!(keyring.keys_for("git-agent-001").count(), 2);
+
+        let hash = Receipt::new("git:clone:repo", "git-agent-001", "trace_abc", true).content_hash;
+        assert!(keyring.verify("git-agent-001", &hash, 1500, &old.sign(&hash)).is_ok());
+        assert!(keyring.verify("git-agent-001", &hash, 2500, &new.sign(&hash)).is_ok());
+        assert!(matches!(
+            keyring.verify("git-agent-001", &hash, 2500, &old.sign(&hash)),
+            Err(SignatureError::KeyNotValid { .. })
+        ));
+        assert!(matches!(
+            keyring.verify("git-agent-001", "other", 1500, &old.sign(&hash)),
+            Err(SignatureError::Invalid(_))
+        ));
+        assert!(matches!(
+            keyring.verify("git-agent-001", &hash, 1500, &key(3, "rogue").sign(&hash)),
+            Err(SignatureError::UnknownKey(_))
+        ));
+
+        let duplicate = format!("{0}\n{0}", toml.lines().take(7).collect::<Vec<_>>().join("\n"));
+        assert!(matches!(Keyring::from_toml(&duplicate), Err(KeyringError::DuplicateKey(_))));
+    }
+
+    #[test]
+    fn test_keyring_rejects_unknown_fields() {
+        let entry = |field: &str| {
+            format!(
+                "[[key]]\nagent_id = \"git-agent-001\"\nkey_id = \"git-agent-001#1\"\npublic_key = \"{}\"\n{}",
+                key(1, "git-agent-001#1").public_key(),
+                field
+            )
+        };
+        assert!(Keyring::from_toml(&entry("valid_until = 2000")).is_ok());
+
+        // A misspelled rotation window must not leave the key valid forever
+        let err = Keyring::from_toml(&entry("valid_untill = 2000")).unwrap_err();
+        assert!(matches!(err, KeyringError::Toml(_)));
+        assert!(err.to_string().contains("valid_untill"));
+        assert!(Keyring::from_toml(&format!("{}\n[[keys]]", entry(""))).is_err());
+    }
+}
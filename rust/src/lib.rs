@@ -0,0 +1,115 @@
+// q-protocol
+// Q Protocol Runtime Enforcement Layer (library crate)
+//
+// Modules:
+//   a2ac           - A2AC(md) expression parser and AST
+//   agent_receipt  - Proto `AgentReceipt` with README SHA-256 signatures
+//   canonical      - Canonical JSON encoding every content hash is taken over
+//   citation       - Receipt references in free text, across every syntax
+//   cube           - Memory cubes and the DayZeroCube wrapper
+//   hex_coordinate - The 0x600 hex coordinate address format
+//   receipt        - Execution receipts (proof of work)
+//   state          - Receipt history per operation, loaded on bootstrap
+//   qmem           - .qmem memory entries and their content hashes
+//   chain          - Hash-chained receipt log per trace and its verifier
+//   merkle         - Merkle tree over trace receipts and inclusion proofs
+//   keyring        - Ed25519 receipt signatures and the agent keyring
+//   brain          - Q Brain backends receipts are fetched and verified from
+//   translate      - Semantic ⇄ hex coordinate registry and translator
+//   enforce        - DayZero enforcer and protocol violations
+//   matcher        - Word-boundary phrase matching for the rules
+//   rules          - Pluggable enforcement rules and the rule registry
+//   rewrite        - Span-based message rewriter, edits and unified diffs
+//   policy         - Declarative policy file: thresholds, phrases, profiles
+//   query          - MEM:QUERY pattern language over receipts
+//   freshness      - Receipt max age and invalidation rules
+//   tokenizer      - BPE token counting over tiktoken rank files
+//   metrics        - Compliance metrics and graduation inputs
+//   report         - JSON and SARIF enforcement reports
+//   error          - Crate error type
+
+pub mod a2ac;
+pub mod agent_receipt;
+pub mod canonical;
+pub mod chain;
+pub mod citation;
+pub mod brain;
+pub mod cube;
+pub mod enforce;
+pub mod error;
+pub mod freshness;
+pub mod hex_coordinate;
+pub mod keyring;
+pub mod matcher;
+pub mod merkle;
+pub mod metrics;
+pub mod policy;
+pub mod qmem;
+pub mod query;
+pub mod receipt;
+pub mod report;
+pub mod rewrite;
+pub mod rules;
+pub mod state;
+pub mod tokenizer;
+pub mod translate;
+
+pub use a2ac::{Chain, Expr, ParseError};
+pub use agent_receipt::AgentReceipt;
+pub use brain::{BrainBackend, BrainError, CoordinateRecord, FileBackend, HttpBackend, MemoryBackend};
+pub use chain::{ChainBreak, ChainError, ReceiptChain};
+pub use citation::{Citation, CitationSyntax};
+pub use cube::{Cube, CubeType, DayZeroCube};
+pub use enforce::{DayZero, Evaluation, ProtocolViolation, ViolationSeverity};
+pub use error::{Error, Result};
+pub use freshness::{FreshnessPolicy, FreshnessRule, Staleness};
+pub use hex_coordinate::{HexCoordinate, SpaceCode};
+pub use keyring::{AgentKey, AgentSigningKey, Keyring, ReceiptSignature, SignatureError};
+pub use matcher::PhraseMatcher;
+pub use merkle::{InclusionProof, MerkleTree, ProofError};
+pub use metrics::DayZeroMetrics;
+pub use policy::{Policy, PolicySettings};
+pub use qmem::{QMem, QMemCoordinate, QMemHeader, QMemReceipt, QMemState};
+pub use query::{MemQuery, QueryError, QueryOutput};
+pub use receipt::{Receipt, ReceiptVerification};
+pub use state::StateCache;
+pub use report::{EnforcementReport, MessageReport};
+pub use rewrite::{Edit, Rewrite};
+pub use rules::{Rule, RuleContext, RulePhase, RuleSet};
+pub use tokenizer::{BpeTokenizer, HeuristicTokenizer, Tokenizer};
+pub use translate::{CoordinateRegistry, Translator};
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Current Unix timestamp in seconds
+pub fn now_unix() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Short BLAKE3-derived identifier: `<prefix>_<12 hex chars>`
+pub(crate) fn short_id(prefix: &str, seed: &[u8]) -> String {
+    format!("{}_{}", prefix, short_hex(seed))
+}
+
+/// 12 hex chars of BLAKE3 over `seed`, the current time, a process-wide
+/// counter and the process id, so calls with the same seed differ even on a
+/// coarse clock
+pub(crate) fn short_hex(seed: &[u8]) -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(seed);
+    hasher.update(&nanos.to_le_bytes());
+    hasher.update(&COUNTER.fetch_add(1, Ordering::Relaxed).to_le_bytes());
+    hasher.update(&std::process::id().to_le_bytes());
+    hasher.finalize().to_hex()[..12].to_string()
+}
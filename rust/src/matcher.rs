@@ -0,0 +1,172 @@
+// matcher.rs
+// Word-boundary phrase matching for the enforcement rules
+//
+// Text is split into words (letters, digits and inner apostrophes); a
+// phrase matches only a run of whole words, so "done" never fires on
+// "abandoned" and "i'll" never fires on "will".
+
+use crate::a2ac::Span;
+
+/// A word of the input, lowercased, with its byte span in the original text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Word {
+    pub text: String,
+    pub span: Span,
+}
+
+/// A phrase occurrence: which phrase, and the bytes it covers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PhraseMatch {
+    pub phrase: usize,
+    pub span: Span,
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric()
+}
+
+fn is_apostrophe(c: char) -> bool {
+    c == '\'' || c == '’'
+}
+
+/// Split text into lowercase words; apostrophes count only between letters
+pub fn words(text: &str) -> Vec<Word> {
+    let mut words = Vec::new();
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        if !is_word_char(c) {
+            continue;
+        }
+
+        let mut end = start + c.len_utf8();
+        let mut word = c.to_lowercase().collect::<String>();
+
+        while let Some(&(i, c)) = chars.peek() {
+            if is_word_char(c) {
+                word.extend(c.to_lowercase());
+            } else if is_apostrophe(c) && text[i + c.len_utf8()..].starts_with(is_word_char) {
+                word.push('\'');
+            } else {
+                break;
+            }
+            end = i + c.len_utf8();
+            chars.next();
+        }
+
+        words.push(Word {
+            text: word,
+            span: Span::new(start, end),
+        });
+    }
+
+    words
+}
+
+/// Matches a fixed set of (possibly multi-word) phrases on word boundaries
+#[derive(Debug, Clone, Default)]
+pub struct PhraseMatcher {
+    phrases: Vec<Vec<String>>,
+}
+
+impl PhraseMatcher {
+    pub fn new<I, S>(phrases: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        PhraseMatcher {
+            phrases: phrases
+                .into_iter()
+                .map(|p| words(p.as_ref()).into_iter().map(|w| w.text).collect())
+                .collect(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.phrases.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.phrases.is_empty()
+    }
+
+    pub fn is_match(&self, text: &str) -> bool {
+        !self.find_all(text).is_empty()
+    }
+
+    /// Every occurrence of every phrase, in text order; longer phrases first at the same start
+    pub fn find_all(&self, text: &str) -> Vec<PhraseMatch> {
+        let words = words(text);
+        let mut matches = Vec::new();
+
+        for start in 0..words.len() {
+            let mut here: Vec<PhraseMatch> = self
+                .phrases
+                .iter()
+                .enumerate()
+                .filter(|(_, phrase)| {
+                    !phrase.is_empty()
+                        && words.len() - start >= phrase.len()
+                        && phrase.iter().zip(&words[start..]).all(|(p, w)| *p == w.text)
+                })
+                .map(|(i, phrase)| PhraseMatch {
+                    phrase: i,
+                    span: Span::new(words[start].span.start, words[start + phrase.len() - 1].span.end),
+                })
+                .collect();
+            here.sort_by_key(|m| std::cmp::Reverse(m.span.end));
+            matches.extend(here);
+        }
+
+        matches
+    }
+
+    /// Number of distinct phrases that occur in the text
+    pub fn count_phrases(&self, text: &str) -> usize {
+        let mut seen: Vec<usize> = self.find_all(text).iter().map(|m| m.phrase).collect();
+        seen.sort_unstable();
+        seen.dedup();
+        seen.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_words() {
+        let text = "I'll  clone ’em: don't—git-hub’s";
+        let got: Vec<_> = words(text).into_iter().map(|w| (w.text, &text[w.span.start..w.span.end])).collect();
+        assert_eq!(
+            got,
+            [
+                ("i'll".to_string(), "I'll"),
+                ("clone".to_string(), "clone"),
+                ("em".to_string(), "em"),
+                ("don't".to_string(), "don't"),
+                ("git".to_string(), "git"),
+                ("hub's".to_string(), "hub’s"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_phrase_boundaries() {
+        let m = PhraseMatcher::new(["done", "i'll", "seems to", "let me"]);
+
+        assert!(m.is_match("Done."));
+        assert!(m.is_match("OK, I’ll do it"));
+        assert!(m.is_match("it seems   to work"));
+        assert!(!m.is_match("the abandoned branch"));
+        assert!(!m.is_match("we will see"));
+        assert!(!m.is_match("it seems fine to me"));
+        assert!(!m.is_match("letme"));
+
+        let found = m.find_all("Let me check: done, done");
+        assert_eq!(found.iter().map(|f| f.phrase).collect::<Vec<_>>(), [3, 0, 0]);
+        assert_eq!(found[0].span, Span::new(0, 6));
+        assert_eq!(m.count_phrases("Let me check: done, done"), 2);
+    }
+}
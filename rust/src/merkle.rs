@@ -0,0 +1,285 @@
+// merkle.rs
+// Merkle tree over a trace's receipts, with compact inclusion proofs
+//
+//   leaf = BLAKE3(0x00 || receipt content_hash)
+//   node = BLAKE3(0x01 || left || right)
+//
+// Levels are built left to right; an unpaired last node is promoted to the
+// next level unchanged. The root of an empty tree is BLAKE3 of nothing. A
+// proof is the leaf's index, the tree size and one sibling per level where
+// the leaf's ancestor had one; the verifier needs nothing else but the root.
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::receipt::Receipt;
+
+const LEAF_PREFIX: u8 = 0x00;
+const NODE_PREFIX: u8 = 0x01;
+
+type Hash = [u8; 32];
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ProofError {
+    #[error("leaf index {index} out of range for {size} leaves")]
+    IndexOutOfRange { index: usize, size: usize },
+
+    #[error("proof has {actual} siblings, tree shape needs {expected}")]
+    PathLength { expected: usize, actual: usize },
+
+    #[error("'{0}' is not a 32-byte hex hash")]
+    InvalidHash(String),
+
+    #[error("proof is for {proof}, receipt hashes to {receipt}")]
+    LeafMismatch { proof: String, receipt: String },
+
+    #[error("receipt contents do not match its stored hash")]
+    TamperedReceipt,
+
+    #[error("computed root {computed} does not match published root {published}")]
+    RootMismatch { computed: String, published: String },
+}
+
+fn leaf_hash(content_hash: &str) -> Hash {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(&[LEAF_PREFIX]);
+    hasher.update(content_hash.as_bytes());
+    *hasher.finalize().as_bytes()
+}
+
+fn node_hash(left: &Hash, right: &Hash) -> Hash {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(&[NODE_PREFIX]);
+    hasher.update(left);
+    hasher.update(right);
+    *hasher.finalize().as_bytes()
+}
+
+fn to_hex(hash: &Hash) -> String {
+    blake3::Hash::from_bytes(*hash).to_hex().to_string()
+}
+
+fn from_hex(hex: &str) -> Result<Hash, ProofError> {
+    blake3::Hash::from_hex(hex)
+        .map(|h| *h.as_bytes())
+        .map_err(|_| ProofError::InvalidHash(hex.to_string()))
+}
+
+// ============================================================================
+// TREE
+// ============================================================================
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MerkleTree {
+    /// `levels[0]` are the leaves, the last level is the root
+    levels: Vec<Vec<Hash>>,
+    leaves: Vec<String>,
+}
+
+impl MerkleTree {
+    /// Tree over content hashes, in trace order
+    pub fn from_hashes<I, S>(content_hashes: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let leaves: Vec<String> = content_hashes.into_iter().map(Into::into).collect();
+        let mut levels = vec![leaves.iter().map(|h| leaf_hash(h)).collect::<Vec<_>>()];
+
+        while levels[levels.len() - 1].len() > 1 {
+            let next = levels[levels.len() - 1]
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => node_hash(left, right),
+                    [single] => *single,
+                    _ => unreachable!(),
+                })
+                .collect();
+            levels.push(next);
+        }
+
+        MerkleTree { levels, leaves }
+    }
+
+    pub fn from_receipts(receipts: &[Receipt]) -> Self {
+        Self::from_hashes(receipts.iter().map(|r| r.content_hash.as_str()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.leaves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.leaves.is_empty()
+    }
+
+    /// Hex root, the value to publish
+    pub fn root(&self) -> String {
+        match self.levels[self.levels.len() - 1].first() {
+            Some(root) => to_hex(root),
+            None => blake3::hash(&[]).to_hex().to_string(),
+        }
+    }
+
+    /// Position of the leaf for `content_hash`
+    pub fn position(&self, content_hash: &str) -> Option<usize> {
+        self.leaves.iter().position(|h| h == content_hash)
+    }
+
+    /// Inclusion proof for the leaf at `index`
+    pub fn prove(&self, index: usize) -> Option<InclusionProof> {
+        let leaf = self.leaves.get(index)?.clone();
+        let mut siblings = Vec::new();
+        let mut i = index;
+
+        for level in &self.levels[..self.levels.len() - 1] {
+            let sibling = if i % 2 == 1 { Some(i - 1) } else { Some(i + 1).filter(|&s| s < level.len()) };
+            if let Some(s) = sibling {
+                siblings.push(to_hex(&level[s]));
+            }
+            i /= 2;
+        }
+
+        Some(InclusionProof {
+            leaf_index: index,
+            tree_size: self.len(),
+            leaf,
+            siblings,
+        })
+    }
+
+    /// Inclusion proof for `receipt`, if it is in the tree
+    pub fn prove_receipt(&self, receipt: &Receipt) -> Option<InclusionProof> {
+        self.prove(self.position(&receipt.content_hash)?)
+    }
+}
+
+// ============================================================================
+// PROOFS
+// ============================================================================
+
+/// Proof that a content hash is leaf `leaf_index` of a tree with `tree_size` leaves
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InclusionProof {
+    pub leaf_index: usize,
+    pub tree_size: usize,
+    /// The receipt's content hash
+    pub leaf: String,
+    /// Sibling hashes from the leaf level up, hex
+    pub siblings: Vec<String>,
+}
+
+impl InclusionProof {
+    /// Recompute the root this proof commits to
+    pub fn compute_root(&self) -> Result<String, ProofError> {
+        let (index, size) = (self.leaf_index, self.tree_size);
+        if index >= size {
+            return Err(ProofError::IndexOutOfRange { index, size });
+        }
+
+        let mut hash = leaf_hash(&self.leaf);
+        let mut siblings = self.siblings.iter();
+        let (mut i, mut n, mut used) = (index, size, 0);
+
+        while n > 1 {
+            let has_sibling = i % 2 == 1 || i + 1 < n;
+            if has_sibling {
+                let sibling = siblings.next().ok_or(ProofError::PathLength {
+                    expected: used + 1,
+                    actual: self.siblings.len(),
+                })?;
+                let sibling = from_hex(sibling)?;
+                hash = if i % 2 == 1 { node_hash(&sibling, &hash) } else { node_hash(&hash, &sibling) };
+                used += 1;
+            }
+            i /= 2;
+            n = n.div_ceil(2);
+        }
+
+        if used != self.siblings.len() {
+            return Err(ProofError::PathLength {
+                expected: used,
+                actual: self.siblings.len(),
+            });
+        }
+        Ok(to_hex(&hash))
+    }
+
+    /// Check the proof against a published root
+    pub fn verify(&self, root: &str) -> Result<(), ProofError> {
+        let computed = self.compute_root()?;
+        if computed.eq_ignore_ascii_case(root) {
+            Ok(())
+        } else {
+            Err(ProofError::RootMismatch {
+                computed,
+                published: root.to_string(),
+            })
+        }
+    }
+
+    /// Check that `receipt` is intact, is the proven leaf, and is under `root`
+    pub fn verify_receipt(&self, receipt: &Receipt, root: &str) -> Result<(), ProofError> {
+        if !receipt.verify_hash() {
+            return Err(ProofError::TamperedReceipt);
+        }
+        if receipt.content_hash != self.leaf {
+            return Err(ProofError::LeafMismatch {
+                proof: self.leaf.clone(),
+                receipt: receipt.content_hash.clone(),
+            });
+        }
+        self.verify(root)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn receipts(n: usize) -> Vec<Receipt> {
+        (0..n)
+            .map(|i| Receipt::success(&format!("step:run:{}", i), "git-agent-001", "trace_abc", "ok"))
+            .collect()
+    }
+
+    #[test]
+    fn test_every_leaf_proves() {
+        for n in 1..=9 {
+            let receipts = receipts(n);
+            let tree = MerkleTree::from_receipts(&receipts);
+            let root = tree.root();
+
+            for receipt in &receipts {
+                let proof = tree.prove_receipt(receipt).unwrap();
+                assert_eq!(proof.verify_receipt(receipt, &root), Ok(()), "n={} i={}", n, proof.leaf_index);
+            }
+        }
+        assert_eq!(MerkleTree::from_hashes(Vec::<String>::new()).root(), blake3::hash(&[]).to_hex().to_string());
+    }
+
+    #[test]
+    fn test_bad_proofs_rejected() {
+        let receipts = receipts(5);
+        let tree = MerkleTree::from_receipts(&receipts);
+        let root = tree.root();
+        let proof = tree.prove(2).unwrap();
+
+        let mut tampered = receipts[2].clone();
+        tampered.result = Some("failed".to_string());
+        assert_eq!(proof.verify_receipt(&tampered, &root), Err(ProofError::TamperedReceipt));
+        assert!(matches!(proof.verify_receipt(&receipts[3], &root), Err(ProofError::LeafMismatch { .. })));
+
+        let moved = InclusionProof { leaf_index: 3, ..proof.clone() };
+        assert!(moved.verify(&root).is_err());
+
+        let short = InclusionProof { siblings: proof.siblings[1..].to_vec(), ..proof.clone() };
+        assert!(matches!(short.verify(&root), Err(ProofError::PathLength { .. })));
+
+        let other_root = MerkleTree::from_receipts(&receipts[..4]).root();
+        assert!(matches!(proof.verify(&other_root), Err(ProofError::RootMismatch { .. })));
+
+        let json = serde_json::to_string(&proof).unwrap();
+        assert_eq!(serde_json::from_str::<InclusionProof>(&json).unwrap(), proof);
+    }
+}
@@ -0,0 +1,330 @@
+// metrics.rs
+// Day Zero compliance metrics (inputs to graduation)
+
+use serde::Serialize;
+use std::collections::{BTreeMap, VecDeque};
+
+use crate::a2ac::Span;
+use crate::enforce::{ProtocolViolation, ViolationSeverity};
+
+/// Messages graduation is judged on unless configured otherwise
+pub const DEFAULT_GRADUATION_WINDOW: usize = 100;
+
+// Graduation targets
+pub const TARGET_K_VALUE: f64 = 20.0; // Below
+pub const TARGET_COORDINATE_USAGE: f64 = 0.95; // Above
+pub const TARGET_RECEIPT_COVERAGE: f64 = 1.0;
+
+/// All-time counters, plus the same metrics over the last `window_size` messages
+#[derive(Debug, Serialize, Clone)]
+pub struct DayZeroMetrics {
+    pub total_messages: usize,
+    pub total_tokens: usize,
+    pub average_tokens: f64,
+    /// Messages that were coordinates
+    pub coordinate_messages: usize,
+    /// Coordinate messages / messages
+    pub coordinate_usage: f64,
+    /// Completion claims backed by a verified receipt / completion claims
+    pub receipt_coverage: f64,
+    pub completion_claims: usize,
+    pub backed_claims: usize,
+    /// Claims per trace, with the ones that lacked a verified receipt
+    pub coverage_by_trace: BTreeMap<String, TraceCoverage>,
+    pub critical_violations: usize,
+    pub violations: Vec<ProtocolViolation>,
+    pub k_value: f64, // Current K (communication cost)
+    pub window_size: usize,
+    /// Graduation metrics over the last `window_size` messages
+    pub window: WindowMetrics,
+    #[serde(skip)]
+    recent: VecDeque<MessageSample>,
+}
+
+/// What one message contributed to the metrics
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MessageSample {
+    pub tokens: usize,
+    pub coordinate: bool,
+    pub claimed: bool,
+    pub backed: bool,
+    pub critical: usize,
+}
+
+/// Graduation metrics over a window of recent messages
+#[derive(Debug, Serialize, Clone, Default, PartialEq)]
+pub struct WindowMetrics {
+    pub messages: usize,
+    pub k_value: f64,
+    pub coordinate_usage: f64,
+    pub receipt_coverage: f64,
+    pub critical_violations: usize,
+}
+
+impl WindowMetrics {
+    /// Every graduation target met; the window itself must be full
+    pub fn meets_targets(&self, window_size: usize) -> bool {
+        self.messages >= window_size
+            && self.k_value < TARGET_K_VALUE
+            && self.coordinate_usage > TARGET_COORDINATE_USAGE
+            && self.receipt_coverage >= TARGET_RECEIPT_COVERAGE
+            && self.critical_violations == 0
+    }
+}
+
+/// Whether a message claimed completion, and whether a verified receipt backed it
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClaimOutcome {
+    Backed,
+    Unbacked(UnbackedClaim),
+}
+
+/// Receipt coverage for one trace
+#[derive(Debug, Serialize, Clone, Default, PartialEq)]
+pub struct TraceCoverage {
+    pub claims: usize,
+    pub backed: usize,
+    pub unbacked: Vec<UnbackedClaim>,
+}
+
+/// A completion claim without a verified receipt
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct UnbackedClaim {
+    /// 1-based message number within the session
+    pub message: usize,
+    /// The claim phrase or receipt reference
+    pub claim: String,
+    pub span: Span,
+    pub reason: String,
+}
+
+impl Default for DayZeroMetrics {
+    fn default() -> Self {
+        Self::with_window(DEFAULT_GRADUATION_WINDOW)
+    }
+}
+
+impl DayZeroMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_window(window_size: usize) -> Self {
+        DayZeroMetrics {
+            total_messages: 0,
+            total_tokens: 0,
+            average_tokens: 0.0,
+            coordinate_messages: 0,
+            coordinate_usage: 0.0,
+            receipt_coverage: 0.0,
+            completion_claims: 0,
+            backed_claims: 0,
+            coverage_by_trace: BTreeMap::new(),
+            critical_violations: 0,
+            violations: Vec::new(),
+            k_value: 0.0,
+            window_size: window_size.max(1),
+            window: WindowMetrics::default(),
+            recent: VecDeque::new(),
+        }
+    }
+
+    /// Resize the window, dropping samples that no longer fit
+    pub fn set_window_size(&mut self, window_size: usize) {
+        self.window_size = window_size.max(1);
+        while self.recent.len() > self.window_size {
+            self.recent.pop_front();
+        }
+        self.window = self.compute_window();
+    }
+
+    /// Count one processed message
+    pub fn record_message(
+        &mut self,
+        trace_id: &str,
+        tokens: usize,
+        coordinate: bool,
+        claim: Option<ClaimOutcome>,
+        violations: &[ProtocolViolation],
+    ) {
+        let critical = violations
+            .iter()
+            .filter(|v| v.severity == ViolationSeverity::Critical)
+            .count();
+        let sample = MessageSample {
+            tokens,
+            coordinate,
+            claimed: claim.is_some(),
+            backed: claim == Some(ClaimOutcome::Backed),
+            critical,
+        };
+
+        self.total_messages += 1;
+        self.total_tokens += tokens;
+        self.average_tokens = self.total_tokens as f64 / self.total_messages as f64;
+        self.k_value = self.average_tokens; // K value: communication cost
+        self.coordinate_messages += usize::from(coordinate);
+        self.coordinate_usage = self.coordinate_messages as f64 / self.total_messages as f64;
+        self.critical_violations += critical;
+        if let Some(claim) = claim {
+            self.record_claim(trace_id, claim);
+        }
+        self.receipt_coverage = self.coverage();
+
+        self.recent.push_back(sample);
+        if self.recent.len() > self.window_size {
+            self.recent.pop_front();
+        }
+        self.window = self.compute_window();
+    }
+
+    fn record_claim(&mut self, trace_id: &str, claim: ClaimOutcome) {
+        self.completion_claims += 1;
+        let trace = self.coverage_by_trace.entry(trace_id.to_string()).or_default();
+        trace.claims += 1;
+        match claim {
+            ClaimOutcome::Backed => {
+                self.backed_claims += 1;
+                trace.backed += 1;
+            }
+            ClaimOutcome::Unbacked(claim) => trace.unbacked.push(claim),
+        }
+    }
+
+    fn compute_window(&self) -> WindowMetrics {
+        let messages = self.recent.len();
+        let tokens: usize = self.recent.iter().map(|s| s.tokens).sum();
+        let coordinates = self.recent.iter().filter(|s| s.coordinate).count();
+        let claims = self.recent.iter().filter(|s| s.claimed).count();
+        let backed = self.recent.iter().filter(|s| s.backed).count();
+
+        WindowMetrics {
+            messages,
+            k_value: if messages == 0 { 0.0 } else { tokens as f64 / messages as f64 },
+            coordinate_usage: if messages == 0 { 0.0 } else { coordinates as f64 / messages as f64 },
+            receipt_coverage: ratio(backed, claims),
+            critical_violations: self.recent.iter().map(|s| s.critical).sum(),
+        }
+    }
+
+    /// Graduation targets met over the current window
+    pub fn graduated(&self) -> bool {
+        self.window.meets_targets(self.window_size)
+    }
+
+    /// Backed / total claims; 1.0 when nothing was claimed
+    pub fn coverage(&self) -> f64 {
+        ratio(self.backed_claims, self.completion_claims)
+    }
+}
+
+impl TraceCoverage {
+    pub fn coverage(&self) -> f64 {
+        ratio(self.backed, self.claims)
+    }
+}
+
+fn ratio(backed: usize, claims: usize) -> f64 {
+    if claims == 0 {
+        1.0
+    } else {
+        backed as f64 / claims as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A coordinate message of `tokens` tokens, no claim, no violations
+    fn coordinate(metrics: &mut DayZeroMetrics, tokens: usize) {
+        metrics.record_message("trace_abc", tokens, true, None, &[]);
+    }
+
+    fn unbacked(message: usize) -> ClaimOutcome {
+        ClaimOutcome::Unbacked(UnbackedClaim {
+            message,
+            claim: "done".to_string(),
+            span: Span::new(0, 4),
+            reason: "no receipt cited".to_string(),
+        })
+    }
+
+    #[test]
+    fn test_window_size_clamped() {
+        assert_eq!(DayZeroMetrics::with_window(0).window_size, 1);
+
+        let mut metrics = DayZeroMetrics::with_window(3);
+        for tokens in [10, 20, 30] {
+            coordinate(&mut metrics, tokens);
+        }
+        metrics.set_window_size(0);
+        assert_eq!(metrics.window_size, 1);
+        assert_eq!((metrics.window.messages, metrics.window.k_value), (1, 30.0));
+    }
+
+    #[test]
+    fn test_window_eviction() {
+        let mut metrics = DayZeroMetrics::with_window(3);
+        let critical = ProtocolViolation::new(ViolationSeverity::Critical, "RECEIPTS_ARE_TRUTH", "x", 0);
+        metrics.record_message("trace_abc", 10, false, Some(unbacked(1)), &[critical]);
+        for tokens in [20, 30, 40, 50] {
+            coordinate(&mut metrics, tokens);
+        }
+
+        // The first message has left the window, but not the all-time counters
+        assert_eq!(metrics.window.messages, 3);
+        assert_eq!(metrics.window.k_value, 40.0);
+        assert_eq!(metrics.window.coordinate_usage, 1.0);
+        assert_eq!(metrics.window.critical_violations, 0);
+        assert_eq!(metrics.window.receipt_coverage, 1.0);
+        assert_eq!((metrics.k_value, metrics.coordinate_usage), (30.0, 0.8));
+        assert_eq!((metrics.critical_violations, metrics.receipt_coverage), (1, 0.0));
+
+        // Shrinking drops the oldest samples first
+        metrics.set_window_size(2);
+        assert_eq!((metrics.window.messages, metrics.window.k_value), (2, 45.0));
+    }
+
+    #[test]
+    fn test_meets_targets_needs_full_window() {
+        let mut metrics = DayZeroMetrics::with_window(3);
+        coordinate(&mut metrics, 5);
+        coordinate(&mut metrics, 5);
+        assert!(!metrics.graduated(), "two perfect messages are not a full window");
+        coordinate(&mut metrics, 5);
+        assert!(metrics.graduated());
+
+        let perfect = WindowMetrics {
+            messages: 4,
+            k_value: 5.0,
+            coordinate_usage: 1.0,
+            receipt_coverage: 1.0,
+            critical_violations: 0,
+        };
+        assert!(perfect.meets_targets(4));
+        assert!(!perfect.meets_targets(5));
+        assert!(!WindowMetrics { k_value: TARGET_K_VALUE, ..perfect.clone() }.meets_targets(4));
+        assert!(!WindowMetrics { coordinate_usage: TARGET_COORDINATE_USAGE, ..perfect.clone() }.meets_targets(4));
+        assert!(!WindowMetrics { critical_violations: 1, ..perfect }.meets_targets(4));
+    }
+
+    #[test]
+    fn test_ratio_without_claims() {
+        assert_eq!(ratio(0, 0), 1.0);
+        assert_eq!(ratio(1, 4), 0.25);
+        assert_eq!(TraceCoverage::default().coverage(), 1.0);
+
+        // Nothing claimed is full coverage, all-time and in the window
+        let mut metrics = DayZeroMetrics::new();
+        assert_eq!(metrics.coverage(), 1.0);
+        metrics.record_message("trace_abc", 10, false, None, &[]);
+        assert_eq!((metrics.receipt_coverage, metrics.window.receipt_coverage), (1.0, 1.0));
+        assert!(metrics.coverage_by_trace.is_empty());
+
+        metrics.record_message("trace_abc", 10, false, Some(ClaimOutcome::Backed), &[]);
+        metrics.record_message("trace_abc", 10, false, Some(unbacked(3)), &[]);
+        assert_eq!((metrics.receipt_coverage, metrics.window.receipt_coverage), (0.5, 0.5));
+        assert_eq!(metrics.coverage_by_trace["trace_abc"].coverage(), 0.5);
+    }
+}
@@ -0,0 +1,403 @@
+// policy.rs
+// Declarative enforcement policy: thresholds, phrase lists, severity
+// overrides and named profiles, loaded from TOML (see policy.toml)
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use thiserror::Error;
+
+use crate::enforce::ViolationSeverity;
+use crate::freshness::FreshnessRule;
+use crate::rules::{
+    PreambleRule, ReceiptsAreTruthRule, RuleSet, SpeculationRule, TokenLimitRule, UseCoordinatesRule,
+    VerboseAckRule, NO_PREAMBLE, NO_SPECULATION, RECEIPTS_ARE_TRUTH, SILENCE_IS_SUCCESS, TOKEN_LIMIT,
+    USE_COORDINATES,
+};
+
+/// The bundled policy: built-in defaults plus training/strict/production
+pub const BUILTIN_POLICY: &str = include_str!("../policy.toml");
+
+#[derive(Debug, Error)]
+pub enum PolicyError {
+    #[error("invalid policy: {0}")]
+    Toml(#[from] toml::de::Error),
+
+    #[error("unknown policy profile '{0}'")]
+    UnknownProfile(String),
+
+    /// A `[severity]` key that names no built-in rule; `profile` is `None`
+    /// for the top level
+    #[error("unknown rule '{rule}' in {} severity overrides", .profile.as_deref().unwrap_or("top-level"))]
+    UnknownRule { rule: String, profile: Option<String> },
+}
+
+// ============================================================================
+// SETTINGS
+// ============================================================================
+
+/// Numeric thresholds; unset fields keep the built-in value
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Limits {
+    pub token_limit: Option<usize>,
+    pub token_target: Option<usize>,
+    pub coordinate_tokens: Option<usize>,
+    /// Messages the graduation metrics are measured over
+    pub graduation_window: Option<usize>,
+}
+
+/// Phrase lists; a set list replaces the built-in one entirely
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Phrases {
+    pub verbose_ack: Option<Vec<String>>,
+    pub speculation: Option<Vec<String>>,
+    pub preamble: Option<Vec<String>>,
+    pub completion_claims: Option<Vec<String>>,
+    pub standard_operations: Option<Vec<String>>,
+}
+
+/// One layer of policy: the file's top level, or a profile
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PolicySettings {
+    pub strict: Option<bool>,
+    pub limits: Limits,
+    pub phrases: Phrases,
+    /// Rule id → severity every violation of that rule is reported at; only
+    /// built-in rules (org rules set theirs with `RuleSet::set_severity`)
+    pub severity: BTreeMap<String, ViolationSeverity>,
+    /// Receipt freshness rules; a set list replaces the built-in one entirely
+    pub freshness: Option<Vec<FreshnessRule>>,
+}
+
+impl PolicySettings {
+    /// `self` with every key set in `overlay` replaced
+    pub fn merge(&self, overlay: &PolicySettings) -> PolicySettings {
+        let mut severity = self.severity.clone();
+        severity.extend(overlay.severity.iter().map(|(k, v)| (k.clone(), *v)));
+
+        PolicySettings {
+            strict: overlay.strict.or(self.strict),
+            limits: Limits {
+                token_limit: overlay.limits.token_limit.or(self.limits.token_limit),
+                token_target: overlay.limits.token_target.or(self.limits.token_target),
+                coordinate_tokens: overlay
+                    .limits
+                    .coordinate_tokens
+                    .or(self.limits.coordinate_tokens),
+                graduation_window: overlay
+                    .limits
+                    .graduation_window
+                    .or(self.limits.graduation_window),
+            },
+            phrases: Phrases {
+                verbose_ack: overlay.phrases.verbose_ack.clone().or_else(|| self.phrases.verbose_ack.clone()),
+                speculation: overlay.phrases.speculation.clone().or_else(|| self.phrases.speculation.clone()),
+                preamble: overlay.phrases.preamble.clone().or_else(|| self.phrases.preamble.clone()),
+                completion_claims: overlay
+                    .phrases
+                    .completion_claims
+                    .clone()
+                    .or_else(|| self.phrases.completion_claims.clone()),
+                standard_operations: overlay
+                    .phrases
+                    .standard_operations
+                    .clone()
+                    .or_else(|| self.phrases.standard_operations.clone()),
+            },
+            severity,
+            freshness: overlay.freshness.clone().or_else(|| self.freshness.clone()),
+        }
+    }
+
+    /// Built-in rules configured by these settings
+    pub fn rule_set(&self) -> RuleSet {
+        let mut rules = RuleSet::builtin();
+        self.configure(&mut rules);
+        rules
+    }
+
+    /// Apply these settings to the built-in rules in `rules`, in place.
+    /// Other rules, the order and what is disabled are left alone, and a
+    /// built-in that was removed stays removed.
+    pub fn configure(&self, rules: &mut RuleSet) {
+        let lowercase = |list: &Vec<String>| list.iter().map(|p| p.to_lowercase()).collect::<Vec<_>>();
+        let (phrases, limits) = (&self.phrases, &self.limits);
+
+        if let (Some(rule), Some(list)) = (rules.get_mut::<VerboseAckRule>(SILENCE_IS_SUCCESS), &phrases.verbose_ack) {
+            rule.set_phrases(lowercase(list));
+        }
+        if let (Some(rule), Some(list)) = (rules.get_mut::<SpeculationRule>(NO_SPECULATION), &phrases.speculation) {
+            rule.set_phrases(lowercase(list));
+        }
+        if let (Some(rule), Some(list)) = (rules.get_mut::<PreambleRule>(NO_PREAMBLE), &phrases.preamble) {
+            rule.set_phrases(lowercase(list));
+        }
+        if let (Some(rule), Some(list)) =
+            (rules.get_mut::<ReceiptsAreTruthRule>(RECEIPTS_ARE_TRUTH), &phrases.completion_claims)
+        {
+            rule.set_claims(lowercase(list));
+        }
+        if let Some(rule) = rules.get_mut::<UseCoordinatesRule>(USE_COORDINATES) {
+            if let Some(list) = &phrases.standard_operations {
+                rule.set_operations(lowercase(list));
+            }
+            rule.coordinate_tokens = limits.coordinate_tokens.unwrap_or(rule.coordinate_tokens);
+        }
+        if let Some(rule) = rules.get_mut::<TokenLimitRule>(TOKEN_LIMIT) {
+            rule.limit = limits.token_limit.unwrap_or(rule.limit);
+            rule.target = limits.token_target.unwrap_or(rule.target);
+        }
+
+        for (id, severity) in &self.severity {
+            rules.set_severity(id, *severity);
+        }
+    }
+}
+
+// ============================================================================
+// POLICY FILE
+// ============================================================================
+
+/// A policy file: top-level settings plus named profiles layered over them
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(from = "PolicyFile", into = "PolicyFile")]
+pub struct Policy {
+    pub base: PolicySettings,
+    pub profiles: BTreeMap<String, PolicySettings>,
+}
+
+/// On-disk form; spelled out so unknown top-level keys are rejected
+#[derive(Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct PolicyFile {
+    strict: Option<bool>,
+    limits: Limits,
+    phrases: Phrases,
+    severity: BTreeMap<String, ViolationSeverity>,
+    freshness: Option<Vec<FreshnessRule>>,
+    profiles: BTreeMap<String, PolicySettings>,
+}
+
+impl Default for PolicyFile {
+    fn default() -> Self {
+        Policy::default().into()
+    }
+}
+
+impl From<PolicyFile> for Policy {
+    fn from(file: PolicyFile) -> Self {
+        Policy {
+            base: PolicySettings {
+                strict: file.strict,
+                limits: file.limits,
+                phrases: file.phrases,
+                severity: file.severity,
+                freshness: file.freshness,
+            },
+            profiles: file.profiles,
+        }
+    }
+}
+
+impl From<Policy> for PolicyFile {
+    fn from(policy: Policy) -> Self {
+        PolicyFile {
+            strict: policy.base.strict,
+            limits: policy.base.limits,
+            phrases: policy.base.phrases,
+            severity: policy.base.severity,
+            freshness: policy.base.freshness,
+            profiles: policy.profiles,
+        }
+    }
+}
+
+impl Policy {
+    /// The bundled policy.toml
+    pub fn builtin() -> Self {
+        Self::from_toml(BUILTIN_POLICY).expect("bundled policy.toml is valid")
+    }
+
+    /// Parse a policy; severity overrides must name built-in rules
+    pub fn from_toml(toml: &str) -> Result<Self, PolicyError> {
+        let policy: Policy = toml::from_str(toml)?;
+        policy.check_rule_ids()?;
+        Ok(policy)
+    }
+
+    /// A misspelt rule id would otherwise leave that rule at its default severity
+    fn check_rule_ids(&self) -> Result<(), PolicyError> {
+        let builtin = RuleSet::builtin();
+        let known = builtin.ids();
+        let layers = std::iter::once((None, &self.base))
+            .chain(self.profiles.iter().map(|(name, settings)| (Some(name), settings)));
+
+        for (profile, settings) in layers {
+            if let Some(rule) = settings.severity.keys().find(|id| !known.contains(&id.as_str())) {
+                return Err(PolicyError::UnknownRule {
+                    rule: rule.clone(),
+                    profile: profile.cloned(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Load a TOML policy file
+    pub fn load(path: impl AsRef<Path>) -> crate::Result<Self> {
+        Ok(Self::from_toml(&std::fs::read_to_string(path)?)?)
+    }
+
+    pub fn profile_names(&self) -> impl Iterator<Item = &str> {
+        self.profiles.keys().map(String::as_str)
+    }
+
+    /// Settings for `profile` layered over the top level; `None` for the top level alone
+    pub fn resolve(&self, profile: Option<&str>) -> Result<PolicySettings, PolicyError> {
+        match profile {
+            None => Ok(self.base.clone()),
+            Some(name) => self
+                .profiles
+                .get(name)
+                .map(|overlay| self.base.merge(overlay))
+                .ok_or_else(|| PolicyError::UnknownProfile(name.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enforce::ProtocolViolation;
+    use crate::rules::{Rule, RuleContext, RulePhase};
+
+    #[test]
+    fn test_builtin_profiles() {
+        let policy = Policy::builtin();
+        assert_eq!(policy.profile_names().collect::<Vec<_>>(), ["production", "strict", "training"]);
+
+        // Top level reproduces the built-in rules: every list and limit the
+        // rules' defaults hard-code is spelled out the same in policy.toml
+        let base = policy.resolve(None).unwrap();
+        assert_eq!(base.strict, Some(false));
+        let (phrases, limits) = (&base.phrases, &base.limits);
+        assert_eq!(phrases.verbose_ack.as_ref(), Some(&VerboseAckRule::default().phrases().to_vec()));
+        assert_eq!(phrases.speculation.as_ref(), Some(&SpeculationRule::default().phrases().to_vec()));
+        assert_eq!(phrases.preamble.as_ref(), Some(&PreambleRule::default().phrases().to_vec()));
+        assert_eq!(phrases.completion_claims.as_ref(), Some(&ReceiptsAreTruthRule::default().claims().to_vec()));
+        assert_eq!(phrases.standard_operations.as_ref(), Some(&UseCoordinatesRule::default().operations().to_vec()));
+        assert_eq!(limits.token_limit, Some(TokenLimitRule::default().limit));
+        assert_eq!(limits.token_target, Some(TokenLimitRule::default().target));
+        assert_eq!(limits.coordinate_tokens, Some(UseCoordinatesRule::default().coordinate_tokens));
+        assert_eq!(limits.graduation_window, Some(crate::metrics::DEFAULT_GRADUATION_WINDOW));
+        assert!(base.severity.is_empty());
+        assert_eq!(base.freshness.as_ref().unwrap(), &crate::freshness::FreshnessPolicy::builtin().rules);
+
+        let training = policy.resolve(Some("training")).unwrap();
+        assert_eq!(training.limits.token_limit, Some(80));
+        assert_eq!(training.limits.coordinate_tokens, Some(5));
+        assert_eq!(training.severity[NO_SPECULATION], ViolationSeverity::Warning);
+
+        let message = "This probably works";
+        let ctx = RuleContext { message, token_count: 60, receipts: &[] };
+        let violations = training.rule_set().check(RulePhase::Silence, &ctx);
+        assert!(violations.iter().all(|v| v.severity == ViolationSeverity::Warning));
+        assert!(!violations.iter().any(|v| v.rule == TOKEN_LIMIT)); // 60 < 80
+
+        let strict = policy.resolve(Some("strict")).unwrap();
+        assert_eq!(strict.strict, Some(true));
+        let violations = strict.rule_set().check(RulePhase::Silence, &ctx);
+        assert!(violations.iter().any(|v| v.rule == TOKEN_LIMIT && v.token_waste == 40));
+
+        assert!(matches!(policy.resolve(Some("nope")), Err(PolicyError::UnknownProfile(_))));
+    }
+
+    #[test]
+    fn test_custom_policy() {
+        let policy = Policy::from_toml(
+            r#"
+            [phrases]
+            preamble = ["Without further ado"]
+
+            [severity]
+            NO_PREAMBLE = "critical"
+
+            [profiles.ops.limits]
+            token_limit = 10
+            "#,
+        )
+        .unwrap();
+
+        let settings = policy.resolve(Some("ops")).unwrap();
+        let rules = settings.rule_set();
+        let ctx = RuleContext { message: "without further ado, the list", token_count: 12, receipts: &[] };
+        let violations = rules.check(RulePhase::Silence, &ctx);
+        let rules_hit: Vec<_> = violations.iter().map(|v| (v.rule.as_str(), v.severity)).collect();
+        assert_eq!(
+            rules_hit,
+            [
+                ("NO_PREAMBLE", ViolationSeverity::Critical),
+                ("USE_COORDINATES", ViolationSeverity::Error),
+                ("TOKEN_LIMIT", ViolationSeverity::Error),
+            ]
+        );
+
+        assert!(Policy::from_toml("strcit = true").is_err());
+        assert!(Policy::from_toml("[limits]\ntoken_limt = 5").is_err());
+        assert!(Policy::from_toml("[severity]\nNO_PREAMBLE = \"fatal\"").is_err());
+
+        let err = Policy::from_toml("[severity]\nNO_PREAMBEL = \"critical\"").unwrap_err();
+        assert!(matches!(&err, PolicyError::UnknownRule { rule, profile: None } if rule == "NO_PREAMBEL"));
+        let err = Policy::from_toml("[profiles.ops.severity]\nTOKEN_LIMT = \"info\"").unwrap_err();
+        assert_eq!(err.to_string(), "unknown rule 'TOKEN_LIMT' in ops severity overrides");
+    }
+
+    /// Org rule flagging "synergy"
+    struct NoBuzzwords;
+
+    impl Rule for NoBuzzwords {
+        fn id(&self) -> &str {
+            "ORG_NO_BUZZWORDS"
+        }
+
+        fn default_severity(&self) -> ViolationSeverity {
+            ViolationSeverity::Info
+        }
+
+        fn check(&self, ctx: &RuleContext<'_>) -> Vec<ProtocolViolation> {
+            if ctx.message.contains("synergy") {
+                vec![self.violation("Say what it does".to_string(), 1)]
+            } else {
+                Vec::new()
+            }
+        }
+    }
+
+    #[test]
+    fn test_configure_keeps_org_rules() {
+        let mut rules = RuleSet::builtin();
+        rules.add(NoBuzzwords);
+        rules.reorder(&["ORG_NO_BUZZWORDS"]);
+        rules.disable(NO_SPECULATION);
+        rules.remove(NO_PREAMBLE);
+
+        let training = Policy::builtin().resolve(Some("training")).unwrap();
+        training.configure(&mut rules);
+
+        // Org rule, order, disabled and removed rules are all kept
+        assert_eq!(rules.ids()[0], "ORG_NO_BUZZWORDS");
+        assert!(!rules.is_enabled(NO_SPECULATION));
+        assert!(rules.position(NO_PREAMBLE).is_none());
+        assert_eq!(rules.get::<TokenLimitRule>(TOKEN_LIMIT).unwrap().limit, 80);
+
+        let ctx = RuleContext { message: "Let me add synergy", token_count: 4, receipts: &[] };
+        let ids: Vec<_> = rules.check(RulePhase::Silence, &ctx).into_iter().map(|v| v.rule).collect();
+        assert_eq!(ids, ["ORG_NO_BUZZWORDS"]);
+
+        // `get` checks the type as well as the id
+        assert!(rules.get::<NoBuzzwords>(TOKEN_LIMIT).is_none());
+    }
+}
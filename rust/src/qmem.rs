@@ -0,0 +1,293 @@
+// qmem.rs
+// .qmem memory entries (definitions/qmem_spec.md) and their content hashes
+//
+// Every `hash` / `content_hash` is BLAKE3 over the canonical encoding
+// (see `canonical`) of the entry with the hash field itself left out.
+
+use serde::{Deserialize, Serialize};
+
+use crate::canonical;
+use crate::keyring::{AgentSigningKey, Keyring, ReceiptSignature, SignatureError};
+use crate::merkle::MerkleTree;
+use crate::now_unix;
+
+pub const QMEM_VERSION: &str = "1.0.0";
+
+// ============================================================================
+// ENTRIES
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct QMemHeader {
+    pub version: String,
+    pub cube_id: String,
+    pub agent_id: String,
+    pub trace_id: String,
+    pub created_at: u64,
+    pub last_modified: u64,
+    pub entry_count: usize,
+    pub total_bytes: usize,
+    /// BLAKE3 of the receipts, states and coordinates (`QMem::compute_hash`)
+    pub content_hash: String,
+    /// Merkle root over the receipt hashes (see `merkle`); `None` when empty
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub merkle_root: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct QMemReceipt {
+    pub receipt_id: String,
+    pub operation: String,
+    pub agent_id: String,
+    pub trace_id: String,
+    pub timestamp: u64,
+    pub success: bool,
+    #[serde(with = "canonical::bytes")]
+    pub result: Vec<u8>,
+    pub error: Option<String>,
+    pub token_count: usize,
+    pub execution_time_ms: u64,
+    pub hash: String,
+    /// Ed25519 signature over `hash`; optional
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<ReceiptSignature>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct QMemState {
+    pub state_id: String,
+    pub timestamp: u64,
+    #[serde(with = "canonical::bytes")]
+    pub context: Vec<u8>,
+    pub token_count: usize,
+    pub message_count: usize,
+    pub hash: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct QMemCoordinate {
+    pub coord_id: String,
+    pub subject: String,
+    pub action: String,
+    pub template: String,
+    pub executor: String,
+    pub usage_count: usize,
+    pub avg_tokens: f64,
+    pub created_at: u64,
+    pub last_used: u64,
+}
+
+impl QMemHeader {
+    pub fn canonical_bytes(&self) -> Vec<u8> {
+        canonical::to_vec(self).unwrap_or_default()
+    }
+}
+
+impl QMemReceipt {
+    /// Canonical encoding of every field except `hash` and `signature`
+    pub fn canonical_bytes(&self) -> Vec<u8> {
+        canonical::to_vec_excluding(self, &["hash", "signature"]).unwrap_or_default()
+    }
+
+    pub fn compute_hash(&self) -> String {
+        canonical::hash(&self.canonical_bytes())
+    }
+
+    /// Store the hash of the current contents; drops any stale signature
+    pub fn sealed(mut self) -> Self {
+        self.hash = self.compute_hash();
+        self.signature = None;
+        self
+    }
+
+    /// Seal and sign with `key`
+    pub fn signed(self, key: &AgentSigningKey) -> Self {
+        let mut receipt = self.sealed();
+        receipt.signature = Some(key.sign(&receipt.hash));
+        receipt
+    }
+
+    /// Check the signature against `keyring`; `Ok(false)` if unsigned and
+    /// the keyring has no key for the agent
+    pub fn verify_signature(&self, keyring: &Keyring) -> Result<bool, SignatureError> {
+        match &self.signature {
+            Some(signature) => keyring
+                .verify(&self.agent_id, &self.hash, self.timestamp, signature)
+                .map(|()| true),
+            None if keyring.requires_signature(&self.agent_id) => {
+                Err(SignatureError::Unsigned(self.agent_id.clone()))
+            }
+            None => Ok(false),
+        }
+    }
+
+    pub fn verify_hash(&self) -> bool {
+        self.hash == self.compute_hash()
+    }
+}
+
+impl QMemState {
+    /// Canonical encoding of every field except `hash`
+    pub fn canonical_bytes(&self) -> Vec<u8> {
+        canonical::to_vec_excluding(self, &["hash"]).unwrap_or_default()
+    }
+
+    pub fn compute_hash(&self) -> String {
+        canonical::hash(&self.canonical_bytes())
+    }
+
+    pub fn sealed(mut self) -> Self {
+        self.hash = self.compute_hash();
+        self
+    }
+
+    pub fn verify_hash(&self) -> bool {
+        self.hash == self.compute_hash()
+    }
+}
+
+impl QMemCoordinate {
+    pub fn canonical_bytes(&self) -> Vec<u8> {
+        canonical::to_vec(self).unwrap_or_default()
+    }
+}
+
+// ============================================================================
+// QMEM
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct QMem {
+    pub header: QMemHeader,
+    pub receipts: Vec<QMemReceipt>,
+    pub states: Vec<QMemState>,
+    pub coordinates: Vec<QMemCoordinate>,
+}
+
+/// What the header's content hash covers
+#[derive(Serialize)]
+struct QMemContent<'a> {
+    receipts: &'a [QMemReceipt],
+    states: &'a [QMemState],
+    coordinates: &'a [QMemCoordinate],
+}
+
+impl QMem {
+    pub fn new(cube_id: &str, agent_id: &str, trace_id: &str) -> Self {
+        let now = now_unix();
+
+        QMem {
+            header: QMemHeader {
+                version: QMEM_VERSION.to_string(),
+                cube_id: cube_id.to_string(),
+                agent_id: agent_id.to_string(),
+                trace_id: trace_id.to_string(),
+                created_at: now,
+                last_modified: now,
+                entry_count: 0,
+                total_bytes: 0,
+                content_hash: String::new(),
+                merkle_root: None,
+            },
+            receipts: Vec::new(),
+            states: Vec::new(),
+            coordinates: Vec::new(),
+        }
+        .sealed()
+    }
+
+    pub fn add_receipt(&mut self, receipt: QMemReceipt) {
+        self.receipts.push(receipt);
+    }
+
+    pub fn add_state(&mut self, state: QMemState) {
+        self.states.push(state);
+    }
+
+    pub fn add_coordinate(&mut self, coordinate: QMemCoordinate) {
+        self.coordinates.push(coordinate);
+    }
+
+    /// BLAKE3 over the canonical receipts, states and coordinates
+    pub fn compute_hash(&self) -> String {
+        let content = QMemContent {
+            receipts: &self.receipts,
+            states: &self.states,
+            coordinates: &self.coordinates,
+        };
+        canonical::hash(&canonical::to_vec(&content).unwrap_or_default())
+    }
+
+    /// Merkle tree over the receipt hashes, in file order
+    pub fn merkle_tree(&self) -> MerkleTree {
+        MerkleTree::from_hashes(self.receipts.iter().map(|r| r.hash.as_str()))
+    }
+
+    fn merkle_root(&self) -> Option<String> {
+        Some(self.merkle_tree().root()).filter(|_| !self.receipts.is_empty())
+    }
+
+    /// Refresh the header's entry count, timestamp, content hash and Merkle root
+    pub fn sealed(mut self) -> Self {
+        self.seal();
+        self
+    }
+
+    pub fn seal(&mut self) {
+        self.header.last_modified = now_unix();
+        self.header.entry_count = self.receipts.len() + self.states.len() + self.coordinates.len();
+        self.header.content_hash = self.compute_hash();
+        self.header.merkle_root = self.merkle_root();
+    }
+
+    /// Header hashes match the content and every entry matches its own hash
+    pub fn verify(&self) -> bool {
+        self.header.content_hash == self.compute_hash()
+            && self.header.merkle_root == self.merkle_root()
+            && self.receipts.iter().all(QMemReceipt::verify_hash)
+            && self.states.iter().all(QMemState::verify_hash)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_qmem_seal_and_tamper() {
+        let receipt = QMemReceipt {
+            receipt_id: "rcpt_abc123".to_string(),
+            operation: "git:clone:repo".to_string(),
+            agent_id: "git-agent-001".to_string(),
+            trace_id: "trace_xyz".to_string(),
+            timestamp: 1_700_000_000,
+            success: true,
+            result: b"cloned".to_vec(),
+            error: None,
+            token_count: 12,
+            execution_time_ms: 340,
+            hash: String::new(),
+            signature: None,
+        }
+        .sealed();
+        assert!(receipt.verify_hash());
+
+        let mut qmem = QMem::new("cube_1", "git-agent-001", "trace_xyz");
+        qmem.add_receipt(receipt);
+        qmem.seal();
+        assert_eq!(qmem.header.entry_count, 1);
+        assert!(qmem.verify());
+
+        let root = qmem.header.merkle_root.clone().unwrap();
+        let proof = qmem.merkle_tree().prove(0).unwrap();
+        assert_eq!(proof.leaf, qmem.receipts[0].hash);
+        assert!(proof.verify(&root).is_ok());
+
+        let mut tampered = qmem.clone();
+        tampered.receipts[0].result = b"failed".to_vec();
+        assert!(!tampered.verify());
+
+        let json = serde_json::to_string(&qmem).unwrap();
+        assert_eq!(serde_json::from_str::<QMem>(&json).unwrap(), qmem);
+    }
+}
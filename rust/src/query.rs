@@ -0,0 +1,428 @@
+// query.rs
+// The MEM:QUERY pattern language over receipts
+//
+//   query     := pattern [ "[" filter ( "," filter )* "]" ] [ "|" aggregate ]
+//   pattern   := glob over the receipt operation (subject:action:context)
+//   filter    := "agent=" id | "trace=" id | "success=" bool
+//              | "since=" unix | "until=" unix
+//   aggregate := "count" [ cmp n ] | "latest" | "any_failed"
+//   cmp       := "==" | "!=" | "<" | "<=" | ">" | ">="
+//
+// A pattern with no wildcard is a segment prefix: `git` and `git:clone`
+// match `git:clone:repo`, `git:cl` does not. `*` matches any run of
+// characters (':' included), `?` any one character. `since` is inclusive,
+// `until` exclusive. `count(receipts)` is accepted for `count`, so the spec's
+// `MEM:QUERY:research|count(receipts)==3` parses. No whitespace is needed,
+// so a query fits in an A2AC `◈ MEM:QUERY:…` expression; the A2AC parser
+// also accepts the spec's spaced `◈ MEM:QUERY:research → count(receipts) == 3`.
+//
+//   ◈ MEM:QUERY:git:*[agent=git-agent-001,success=true]|latest
+//   ◈ MEM:QUERY:deploy:*[trace=trace_abc,since=1767225600]|any_failed
+
+use serde::Serialize;
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+use crate::receipt::Receipt;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum QueryError {
+    #[error("empty query pattern")]
+    EmptyPattern,
+
+    #[error("unclosed '[' in query filters")]
+    UnclosedFilters,
+
+    #[error("unknown query filter '{0}' (expected agent, trace, success, since or until)")]
+    UnknownFilter(String),
+
+    #[error("invalid value '{value}' for query filter '{filter}'")]
+    InvalidFilter { filter: String, value: String },
+
+    #[error("unknown aggregate '{0}' (expected count, latest or any_failed)")]
+    UnknownAggregate(String),
+
+    #[error("invalid count comparison '{0}'")]
+    InvalidComparison(String),
+}
+
+// ============================================================================
+// AST
+// ============================================================================
+
+/// Receipt attributes a query can narrow on
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct QueryFilters {
+    pub agent_id: Option<String>,
+    pub trace_id: Option<String>,
+    pub success: Option<bool>,
+    /// Unix seconds, inclusive
+    pub since: Option<u64>,
+    /// Unix seconds, exclusive
+    pub until: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Comparison {
+    const ALL: [(&'static str, Comparison); 6] = [
+        ("==", Comparison::Eq),
+        ("!=", Comparison::Ne),
+        ("<=", Comparison::Le),
+        (">=", Comparison::Ge),
+        ("<", Comparison::Lt),
+        (">", Comparison::Gt),
+    ];
+
+    pub fn holds(&self, left: usize, right: usize) -> bool {
+        match self {
+            Comparison::Eq => left == right,
+            Comparison::Ne => left != right,
+            Comparison::Lt => left < right,
+            Comparison::Le => left <= right,
+            Comparison::Gt => left > right,
+            Comparison::Ge => left >= right,
+        }
+    }
+
+    fn symbol(&self) -> &'static str {
+        Self::ALL.iter().find(|(_, c)| c == self).map(|(s, _)| *s).unwrap_or("==")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Aggregate {
+    /// Number of matches, optionally compared against a number
+    Count(Option<(Comparison, usize)>),
+    /// Most recent match
+    Latest,
+    /// Whether any match failed
+    AnyFailed,
+}
+
+/// A parsed `MEM:QUERY` pattern
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemQuery {
+    pub pattern: String,
+    pub filters: QueryFilters,
+    pub aggregate: Option<Aggregate>,
+}
+
+/// What a query evaluates to
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum QueryOutput {
+    /// Every match, oldest first
+    Receipts { receipts: Vec<Receipt> },
+    /// `holds` is set when the query compared the count
+    Count { count: usize, holds: Option<bool> },
+    Latest { receipt: Option<Box<Receipt>> },
+    AnyFailed { any_failed: bool },
+}
+
+impl QueryOutput {
+    /// Whether the answer is "yes": some match, a comparison that holds, or a failure found
+    pub fn is_truthy(&self) -> bool {
+        match self {
+            QueryOutput::Receipts { receipts } => !receipts.is_empty(),
+            QueryOutput::Count { count, holds } => holds.unwrap_or(*count > 0),
+            QueryOutput::Latest { receipt } => receipt.is_some(),
+            QueryOutput::AnyFailed { any_failed } => *any_failed,
+        }
+    }
+}
+
+// ============================================================================
+// EVALUATION
+// ============================================================================
+
+impl MemQuery {
+    /// Every receipt whose operation matches `pattern`
+    pub fn pattern(pattern: &str) -> Self {
+        MemQuery {
+            pattern: pattern.to_string(),
+            filters: QueryFilters::default(),
+            aggregate: None,
+        }
+    }
+
+    /// The same query without its aggregate: what a backend has to return
+    pub fn selection(&self) -> MemQuery {
+        MemQuery {
+            aggregate: None,
+            ..self.clone()
+        }
+    }
+
+    pub fn matches(&self, receipt: &Receipt) -> bool {
+        self.matches_with(receipt, |pattern, r| matches_operation(pattern, &r.operation))
+    }
+
+    /// `matches`, with `covers` deciding whether the pattern matches the
+    /// receipt's operation (DayZero also tries its translator key)
+    pub fn matches_with(&self, receipt: &Receipt, covers: impl Fn(&str, &Receipt) -> bool) -> bool {
+        let f = &self.filters;
+        covers(&self.pattern, receipt)
+            && f.agent_id.as_ref().is_none_or(|a| *a == receipt.agent_id)
+            && f.trace_id.as_ref().is_none_or(|t| *t == receipt.trace_id)
+            && f.success.is_none_or(|s| s == receipt.success)
+            && f.since.is_none_or(|since| receipt.timestamp >= since)
+            && f.until.is_none_or(|until| receipt.timestamp < until)
+    }
+
+    /// Matching receipts, oldest first (ties keep input order)
+    pub fn select<'a>(&self, receipts: impl IntoIterator<Item = &'a Receipt>) -> Vec<&'a Receipt> {
+        self.select_with(receipts, |pattern, r| matches_operation(pattern, &r.operation))
+    }
+
+    /// `select`, matching operations with `covers` (see `matches_with`)
+    pub fn select_with<'a>(
+        &self,
+        receipts: impl IntoIterator<Item = &'a Receipt>,
+        covers: impl Fn(&str, &Receipt) -> bool,
+    ) -> Vec<&'a Receipt> {
+        let mut selected: Vec<&Receipt> =
+            receipts.into_iter().filter(|r| self.matches_with(r, &covers)).collect();
+        selected.sort_by_key(|r| r.timestamp);
+        selected
+    }
+
+    /// Select, then aggregate
+    pub fn evaluate<'a>(&self, receipts: impl IntoIterator<Item = &'a Receipt>) -> QueryOutput {
+        self.evaluate_with(receipts, |pattern, r| matches_operation(pattern, &r.operation))
+    }
+
+    /// `evaluate`, matching operations with `covers` (see `matches_with`)
+    pub fn evaluate_with<'a>(
+        &self,
+        receipts: impl IntoIterator<Item = &'a Receipt>,
+        covers: impl Fn(&str, &Receipt) -> bool,
+    ) -> QueryOutput {
+        let selected = self.select_with(receipts, covers);
+        match self.aggregate {
+            None => QueryOutput::Receipts {
+                receipts: selected.into_iter().cloned().collect(),
+            },
+            Some(Aggregate::Count(comparison)) => QueryOutput::Count {
+                count: selected.len(),
+                holds: comparison.map(|(cmp, n)| cmp.holds(selected.len(), n)),
+            },
+            Some(Aggregate::Latest) => QueryOutput::Latest {
+                receipt: selected.last().map(|r| Box::new((*r).clone())),
+            },
+            Some(Aggregate::AnyFailed) => QueryOutput::AnyFailed {
+                any_failed: selected.iter().any(|r| !r.success),
+            },
+        }
+    }
+}
+
+/// Glob match, or segment prefix match when `pattern` has no wildcard
+pub fn matches_operation(pattern: &str, operation: &str) -> bool {
+    if !pattern.contains(['*', '?']) {
+        return operation
+            .strip_prefix(pattern)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with(':'));
+    }
+
+    let (p, s): (Vec<char>, Vec<char>) = (pattern.chars().collect(), operation.chars().collect());
+    let (mut pi, mut si) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while si < s.len() {
+        match p.get(pi) {
+            Some('*') => {
+                backtrack = Some((pi, si));
+                pi += 1;
+            }
+            Some(&c) if c == '?' || c == s[si] => {
+                pi += 1;
+                si += 1;
+            }
+            _ => match backtrack {
+                Some((star, from)) => {
+                    pi = star + 1;
+                    si = from + 1;
+                    backtrack = Some((star, from + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    p[pi..].iter().all(|&c| c == '*')
+}
+
+// ============================================================================
+// PARSING
+// ============================================================================
+
+impl FromStr for MemQuery {
+    type Err = QueryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let s = s.strip_prefix("MEM:QUERY:").unwrap_or(s);
+
+        let (head, aggregate) = match s.split_once('|') {
+            Some((head, aggregate)) => (head.trim(), Some(parse_aggregate(aggregate)?)),
+            None => (s, None),
+        };
+
+        let (pattern, filters) = match head.strip_suffix(']') {
+            Some(rest) => {
+                let (pattern, filters) = rest.rsplit_once('[').ok_or(QueryError::UnclosedFilters)?;
+                (pattern.trim(), parse_filters(filters)?)
+            }
+            None if head.contains('[') => return Err(QueryError::UnclosedFilters),
+            None => (head, QueryFilters::default()),
+        };
+
+        if pattern.is_empty() {
+            return Err(QueryError::EmptyPattern);
+        }
+        Ok(MemQuery {
+            pattern: pattern.to_string(),
+            filters,
+            aggregate,
+        })
+    }
+}
+
+fn parse_filters(filters: &str) -> Result<QueryFilters, QueryError> {
+    let mut parsed = QueryFilters::default();
+
+    for filter in filters.split(',').map(str::trim).filter(|f| !f.is_empty()) {
+        let (key, value) = filter
+            .split_once('=')
+            .map(|(k, v)| (k.trim(), v.trim()))
+            .ok_or_else(|| QueryError::UnknownFilter(filter.to_string()))?;
+        let invalid = || QueryError::InvalidFilter {
+            filter: key.to_string(),
+            value: value.to_string(),
+        };
+
+        match key {
+            "agent" => parsed.agent_id = Some(value.to_string()),
+            "trace" => parsed.trace_id = Some(value.to_string()),
+            "success" => parsed.success = Some(value.parse().map_err(|_| invalid())?),
+            "since" => parsed.since = Some(value.parse().map_err(|_| invalid())?),
+            "until" => parsed.until = Some(value.parse().map_err(|_| invalid())?),
+            other => return Err(QueryError::UnknownFilter(other.to_string())),
+        }
+    }
+    Ok(parsed)
+}
+
+fn parse_aggregate(aggregate: &str) -> Result<Aggregate, QueryError> {
+    let aggregate = aggregate.trim();
+    match aggregate {
+        "latest" => return Ok(Aggregate::Latest),
+        "any_failed" | "any-failed" => return Ok(Aggregate::AnyFailed),
+        _ => {}
+    }
+
+    let Some(rest) = aggregate.strip_prefix("count") else {
+        return Err(QueryError::UnknownAggregate(aggregate.to_string()));
+    };
+    let rest = rest.strip_prefix("(receipts)").unwrap_or(rest).trim();
+    if rest.is_empty() {
+        return Ok(Aggregate::Count(None));
+    }
+
+    let invalid = || QueryError::InvalidComparison(rest.to_string());
+    let (symbol, cmp) = Comparison::ALL
+        .iter()
+        .find(|(symbol, _)| rest.starts_with(symbol))
+        .ok_or_else(invalid)?;
+    let n = rest[symbol.len()..].trim().parse().map_err(|_| invalid())?;
+    Ok(Aggregate::Count(Some((*cmp, n))))
+}
+
+impl fmt::Display for MemQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.pattern)?;
+
+        let q = &self.filters;
+        let filters: Vec<String> = [
+            q.agent_id.as_ref().map(|a| format!("agent={}", a)),
+            q.trace_id.as_ref().map(|t| format!("trace={}", t)),
+            q.success.map(|s| format!("success={}", s)),
+            q.since.map(|s| format!("since={}", s)),
+            q.until.map(|u| format!("until={}", u)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        if !filters.is_empty() {
+            write!(f, "[{}]", filters.join(","))?;
+        }
+
+        match self.aggregate {
+            None => Ok(()),
+            Some(Aggregate::Count(None)) => write!(f, "|count"),
+            Some(Aggregate::Count(Some((cmp, n)))) => write!(f, "|count{}{}", cmp.symbol(), n),
+            Some(Aggregate::Latest) => write!(f, "|latest"),
+            Some(Aggregate::AnyFailed) => write!(f, "|any_failed"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn receipt(op: &str, agent: &str, success: bool, timestamp: u64) -> Receipt {
+        Receipt {
+            timestamp,
+            ..Receipt::new(op, agent, "trace_abc", success)
+        }
+        .sealed()
+    }
+
+    #[test]
+    fn test_query_language() {
+        let receipts = [
+            receipt("git:clone:github.com/user/repo", "git-agent-001", true, 100),
+            receipt("git:push:github.com/user/repo", "git-agent-001", false, 200),
+            receipt("research:paper:a", "research-agent", true, 300),
+            receipt("research:paper:b", "research-agent", true, 400),
+            receipt("research:paper:c", "research-agent", true, 500),
+        ];
+        let run = |q: &str| q.parse::<MemQuery>().unwrap().evaluate(&receipts);
+
+        assert!(matches_operation("git", "git:clone:repo"));
+        assert!(!matches_operation("git:cl", "git:clone:repo"));
+        assert!(matches_operation("*:push:*", "git:push:github.com/user/repo"));
+        assert!(matches_operation("git:???ne:*", "git:clone:repo"));
+
+        assert_eq!(run("MEM:QUERY:research|count(receipts)==3"), QueryOutput::Count { count: 3, holds: Some(true) });
+        assert_eq!(run("git:*|any_failed"), QueryOutput::AnyFailed { any_failed: true });
+        assert_eq!(run("git:*[success=true]|any-failed"), QueryOutput::AnyFailed { any_failed: false });
+        assert_eq!(run("*[agent=git-agent-001,since=150,until=300]|count"), QueryOutput::Count { count: 1, holds: None });
+        assert_eq!(run("research|latest"), QueryOutput::Latest { receipt: Some(Box::new(receipts[4].clone())) });
+        assert!(!run("deploy:*").is_truthy());
+
+        let query: MemQuery = "git:*[agent=git-agent-001,success=false]|count>=1".parse().unwrap();
+        assert_eq!(query.to_string().parse::<MemQuery>().unwrap(), query);
+        let chain = crate::a2ac::parse(&format!("◈ MEM:QUERY:{} → deploy:prod", query)).unwrap();
+        let crate::a2ac::Expr::Query(embedded) = chain.first() else { panic!("expected query") };
+        assert_eq!(embedded.pattern.parse::<MemQuery>().unwrap(), query);
+
+        let err = |q: &str| q.parse::<MemQuery>().unwrap_err();
+        assert_eq!(err("[agent=x]"), QueryError::EmptyPattern);
+        assert_eq!(err("git:*[agent=x"), QueryError::UnclosedFilters);
+        assert_eq!(err("git:*[owner=x]"), QueryError::UnknownFilter("owner".into()));
+        assert!(matches!(err("git:*[since=yesterday]"), QueryError::InvalidFilter { .. }));
+        assert_eq!(err("git|sum"), QueryError::UnknownAggregate("sum".into()));
+        assert_eq!(err("git|count~3"), QueryError::InvalidComparison("~3".into()));
+    }
+}
@@ -0,0 +1,232 @@
+// receipt.rs
+// Execution receipts: proof of completed work ("Receipts are Truth")
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::canonical;
+use crate::freshness::INVALIDATE_PREFIX;
+use crate::keyring::{AgentSigningKey, Keyring, ReceiptSignature, SignatureError};
+use crate::{now_unix, short_id};
+
+// ============================================================================
+// RECEIPT
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Receipt {
+    pub receipt_id: String,
+    pub operation: String,
+    pub agent_id: String,
+    pub trace_id: String,
+    pub timestamp: u64,
+    pub success: bool,
+    pub result: Option<String>,
+    pub error: Option<String>,
+    pub token_count: usize,
+    /// `content_hash` of the previous receipt in the trace; `None` for the first
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prev_hash: Option<String>,
+    /// BLAKE3 of `canonical_bytes()`, hex; empty until sealed
+    #[serde(default)]
+    pub content_hash: String,
+    /// Ed25519 signature over `content_hash`; optional
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<ReceiptSignature>,
+}
+
+impl Receipt {
+    /// Create a receipt for an operation, stamped now with a fresh `rcpt_` id
+    pub fn new(operation: &str, agent_id: &str, trace_id: &str, success: bool) -> Self {
+        let seed = format!("{}|{}|{}", operation, agent_id, trace_id);
+
+        Receipt {
+            receipt_id: short_id("rcpt", seed.as_bytes()),
+            operation: operation.to_string(),
+            agent_id: agent_id.to_string(),
+            trace_id: trace_id.to_string(),
+            timestamp: now_unix(),
+            success,
+            result: None,
+            error: None,
+            token_count: 0,
+            prev_hash: None,
+            content_hash: String::new(),
+            signature: None,
+        }
+        .sealed()
+    }
+
+    /// Successful receipt carrying a result
+    pub fn success(operation: &str, agent_id: &str, trace_id: &str, result: &str) -> Self {
+        Receipt {
+            result: Some(result.to_string()),
+            ..Receipt::new(operation, agent_id, trace_id, true)
+        }
+        .sealed()
+    }
+
+    /// Failed receipt carrying an error
+    pub fn failure(operation: &str, agent_id: &str, trace_id: &str, error: &str) -> Self {
+        Receipt {
+            error: Some(error.to_string()),
+            ..Receipt::new(operation, agent_id, trace_id, false)
+        }
+        .sealed()
+    }
+
+    /// Explicit invalidation: receipts for operations matching `pattern`
+    /// recorded before this one stop counting as prior work (see `freshness`)
+    pub fn invalidating(pattern: &str, agent_id: &str, trace_id: &str) -> Self {
+        Receipt::new(&format!("{}{}", INVALIDATE_PREFIX, pattern), agent_id, trace_id, true)
+    }
+
+    pub fn with_token_count(mut self, token_count: usize) -> Self {
+        self.token_count = token_count;
+        self.sealed()
+    }
+
+    /// Link after the receipt whose content hash is `prev_hash` (see `chain`)
+    pub fn linked_to(mut self, prev_hash: Option<String>) -> Self {
+        self.prev_hash = prev_hash;
+        self.sealed()
+    }
+
+    /// Canonical encoding (see `canonical`) of every field except
+    /// `content_hash` and `signature`
+    pub fn canonical_bytes(&self) -> Vec<u8> {
+        canonical::to_vec_excluding(self, &["content_hash", "signature"]).unwrap_or_default()
+    }
+
+    /// BLAKE3 of the canonical encoding, hex
+    pub fn compute_hash(&self) -> String {
+        canonical::hash(&self.canonical_bytes())
+    }
+
+    /// Store the hash of the current contents; drops any stale signature
+    pub fn sealed(mut self) -> Self {
+        self.content_hash = self.compute_hash();
+        self.signature = None;
+        self
+    }
+
+    /// Seal and sign with `key`
+    pub fn signed(self, key: &AgentSigningKey) -> Self {
+        let mut receipt = self.sealed();
+        receipt.signature = Some(key.sign(&receipt.content_hash));
+        receipt
+    }
+
+    /// Check the signature against `keyring`; `Ok(false)` if unsigned and
+    /// the keyring has no key for the agent
+    pub fn verify_signature(&self, keyring: &Keyring) -> Result<bool, SignatureError> {
+        match &self.signature {
+            Some(signature) => keyring
+                .verify(&self.agent_id, &self.content_hash, self.timestamp, signature)
+                .map(|()| true),
+            None if keyring.requires_signature(&self.agent_id) => {
+                Err(SignatureError::Unsigned(self.agent_id.clone()))
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Check the stored hash against the contents
+    pub fn verify_hash(&self) -> bool {
+        self.content_hash == self.compute_hash()
+    }
+
+    /// Full verification of a fetched receipt against the trace citing it
+    pub fn verify(&self, trace_id: &str) -> ReceiptVerification {
+        let actual = self.compute_hash();
+        if self.content_hash != actual {
+            ReceiptVerification::HashMismatch {
+                stored: self.content_hash.clone(),
+                computed: actual,
+            }
+        } else if self.trace_id != trace_id {
+            ReceiptVerification::TraceMismatch {
+                expected: trace_id.to_string(),
+                actual: self.trace_id.clone(),
+            }
+        } else {
+            ReceiptVerification::Valid
+        }
+    }
+}
+
+// ============================================================================
+// VERIFICATION
+// ============================================================================
+
+/// Outcome of verifying a cited receipt
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReceiptVerification {
+    Valid,
+    /// No receipt with that id in the backend
+    Missing,
+    /// Contents do not hash to the stored hash: tampered or corrupt
+    HashMismatch { stored: String, computed: String },
+    /// Genuine receipt, but from another trace
+    TraceMismatch { expected: String, actual: String },
+    /// Not signed by a key the keyring has for the receipt's agent, or
+    /// unsigned although the keyring has one
+    Signature(SignatureError),
+}
+
+impl ReceiptVerification {
+    pub fn is_valid(&self) -> bool {
+        *self == ReceiptVerification::Valid
+    }
+}
+
+impl fmt::Display for ReceiptVerification {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReceiptVerification::Valid => f.write_str("valid"),
+            ReceiptVerification::Missing => f.write_str("not found"),
+            ReceiptVerification::HashMismatch { stored, computed } => {
+                write!(f, "hash mismatch (stored {}, computed {})", stored, computed)
+            }
+            ReceiptVerification::TraceMismatch { expected, actual } => {
+                write!(f, "belongs to trace {}, not {}", actual, expected)
+            }
+            ReceiptVerification::Signature(e) => write!(f, "bad signature: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify_outcomes() {
+        let receipt = Receipt::success("git:clone:repo", "git-agent-001", "trace_abc", "cloned")
+            .with_token_count(12);
+        assert_eq!(receipt.content_hash.len(), 64);
+        assert!(receipt.verify_hash());
+        assert_eq!(receipt.verify("trace_abc"), ReceiptVerification::Valid);
+
+        let mut tampered = receipt.clone();
+        tampered.success = false;
+        assert!(matches!(tampered.verify("trace_abc"), ReceiptVerification::HashMismatch { .. }));
+
+        assert_eq!(
+            receipt.verify("trace_xyz"),
+            ReceiptVerification::TraceMismatch {
+                expected: "trace_xyz".to_string(),
+                actual: "trace_abc".to_string()
+            }
+        );
+
+        let json = serde_json::to_string(&receipt).unwrap();
+        assert_eq!(serde_json::from_str::<Receipt>(&json).unwrap(), receipt);
+
+        // Retries of one operation get distinct ids, however coarse the clock
+        let retries: std::collections::HashSet<_> = (0..1000)
+            .map(|_| Receipt::new("git:push:repo", "git-agent-001", "trace_abc", false).receipt_id)
+            .collect();
+        assert_eq!(retries.len(), 1000);
+    }
+}
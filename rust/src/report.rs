@@ -0,0 +1,200 @@
+// report.rs
+// Machine-readable enforcement results: JSON and SARIF 2.1.0
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::enforce::{DayZero, Evaluation, ProtocolViolation, ViolationSeverity};
+use crate::metrics::DayZeroMetrics;
+
+pub const SARIF_VERSION: &str = "2.1.0";
+pub const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// Enforcement result for one input message
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MessageReport {
+    /// 1-based input line the message came from
+    pub line: usize,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub optimized: Option<String>,
+    pub blocked: bool,
+    pub violations: Vec<ProtocolViolation>,
+}
+
+impl MessageReport {
+    pub fn new(line: usize, message: &str, evaluation: &Evaluation) -> Self {
+        MessageReport {
+            line,
+            message: message.to_string(),
+            optimized: evaluation.optimized.clone(),
+            blocked: evaluation.is_blocked(),
+            violations: evaluation.violations.clone(),
+        }
+    }
+}
+
+/// Everything one enforcement run produced
+#[derive(Debug, Clone, Serialize)]
+pub struct EnforcementReport {
+    pub agent_id: String,
+    pub trace_id: String,
+    pub graduated: bool,
+    pub metrics: DayZeroMetrics,
+    pub messages: Vec<MessageReport>,
+}
+
+impl EnforcementReport {
+    pub fn new(enforcer: &DayZero, messages: Vec<MessageReport>) -> Self {
+        EnforcementReport {
+            agent_id: enforcer.agent_id().to_string(),
+            trace_id: enforcer.trace_id().to_string(),
+            graduated: enforcer.check_graduation(),
+            metrics: enforcer.get_metrics().clone(),
+            messages,
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        serde_json::to_value(self).unwrap_or(Value::Null)
+    }
+
+    /// SARIF log with one result per violation; `artifact_uri` names the input.
+    /// A violation's span becomes `startColumn`/`endColumn` in code points over
+    /// the message text (the snippet), which is the line itself for plain-text input
+    pub fn to_sarif(&self, artifact_uri: &str) -> Value {
+        let mut rule_ids: Vec<&str> = Vec::new();
+        let mut results = Vec::new();
+
+        for report in &self.messages {
+            for v in &report.violations {
+                let rule_index = match rule_ids.iter().position(|id| *id == v.rule) {
+                    Some(i) => i,
+                    None => {
+                        rule_ids.push(&v.rule);
+                        rule_ids.len() - 1
+                    }
+                };
+
+                let mut region = json!({
+                    "startLine": report.line,
+                    "snippet": { "text": report.message },
+                });
+                if let Some(span) = v.span {
+                    region["startColumn"] = json!(sarif_column(&report.message, span.start));
+                    region["endColumn"] = json!(sarif_column(&report.message, span.end));
+                }
+
+                results.push(json!({
+                    "ruleId": v.rule,
+                    "ruleIndex": rule_index,
+                    "level": sarif_level(v.severity),
+                    "message": { "text": v.message },
+                    "locations": [{
+                        "physicalLocation": {
+                            "artifactLocation": { "uri": artifact_uri },
+                            "region": region,
+                        },
+                    }],
+                    "properties": {
+                        "severity": v.severity,
+                        "tokenWaste": v.token_waste,
+                        "span": v.span,
+                        "fix": v.fix,
+                        "blocked": report.blocked,
+                    },
+                }));
+            }
+        }
+
+        let rules: Vec<Value> = rule_ids.iter().map(|id| json!({ "id": id })).collect();
+
+        json!({
+            "$schema": SARIF_SCHEMA,
+            "version": SARIF_VERSION,
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "day_zero",
+                        "version": env!("CARGO_PKG_VERSION"),
+                        "informationUri": env!("CARGO_PKG_REPOSITORY"),
+                        "rules": rules,
+                    },
+                },
+                "results": results,
+                "columnKind": "unicodeCodePoints",
+                "properties": {
+                    "agentId": self.agent_id,
+                    "traceId": self.trace_id,
+                    "graduated": self.graduated,
+                    "kValue": self.metrics.k_value,
+                    "receiptCoverage": self.metrics.receipt_coverage,
+                },
+            }],
+        })
+    }
+}
+
+/// SARIF has no "critical"; it maps to "error"
+pub fn sarif_level(severity: ViolationSeverity) -> &'static str {
+    match severity {
+        ViolationSeverity::Info => "note",
+        ViolationSeverity::Warning => "warning",
+        ViolationSeverity::Error | ViolationSeverity::Critical => "error",
+    }
+}
+
+/// 1-based code-point column of byte offset `at` in `text`
+fn sarif_column(text: &str, at: usize) -> usize {
+    text.get(..at).map_or(0, |prefix| prefix.chars().count()) + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::brain::MemoryBackend;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_json_and_sarif() {
+        let mut dz = DayZero::new(
+            "test-agent".to_string(),
+            "trace-123".to_string(),
+            "http://brain".to_string(),
+        )
+        .with_backend(Arc::new(MemoryBackend::new()));
+
+        let message = "Let me say it is done";
+        let evaluation = dz.evaluate_outgoing(message).await;
+        let report = EnforcementReport::new(&dz, vec![MessageReport::new(7, message, &evaluation)]);
+
+        let json = report.to_json();
+        let violations = json["messages"][0]["violations"].as_array().unwrap();
+        let preamble = violations.iter().find(|v| v["rule"] == "NO_PREAMBLE").unwrap();
+        assert_eq!(preamble["severity"], "warning");
+        assert_eq!(preamble["span"], json!({ "start": 0, "end": 6 }));
+        assert_eq!(preamble["fix"]["replacement"], "S");
+        let parsed: MessageReport = serde_json::from_value(json["messages"][0].clone()).unwrap();
+        assert_eq!(parsed.violations, evaluation.violations);
+
+        let sarif = report.to_sarif("outgoing.txt");
+        assert_eq!(sarif["version"], "2.1.0");
+        let results = sarif["runs"][0]["results"].as_array().unwrap();
+        assert_eq!(results.len(), evaluation.violations.len());
+        let critical = results.iter().find(|r| r["ruleId"] == "RECEIPTS_ARE_TRUTH").unwrap();
+        assert_eq!(critical["level"], "error");
+        assert_eq!(critical["properties"]["severity"], "critical");
+        let region = &critical["locations"][0]["physicalLocation"]["region"];
+        assert_eq!(region["startLine"], 7);
+        // "done" is bytes 17..21 of the message
+        assert_eq!(region["startColumn"], 18);
+        assert_eq!(region["endColumn"], 22);
+        assert_eq!(region["snippet"]["text"], message);
+        assert_eq!(sarif["runs"][0]["columnKind"], "unicodeCodePoints");
+        let preamble = results.iter().find(|r| r["ruleId"] == "NO_PREAMBLE").unwrap();
+        let region = &preamble["locations"][0]["physicalLocation"]["region"];
+        assert_eq!((region["startColumn"].clone(), region["endColumn"].clone()), (json!(1), json!(7)));
+        let rules = sarif["runs"][0]["tool"]["driver"]["rules"].as_array().unwrap();
+        assert_eq!(rules[critical["ruleIndex"].as_u64().unwrap() as usize]["id"], "RECEIPTS_ARE_TRUTH");
+    }
+}
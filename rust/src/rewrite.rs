@@ -0,0 +1,352 @@
+// rewrite.rs
+// Span-based message rewriting
+//
+// Rules propose edits against the original message; the rewriter drops
+// edits touching protected ranges (code, coordinates, receipts, URLs),
+// resolves overlaps and splices the rest in. Everything outside an edit,
+// casing included, is left byte-for-byte intact.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::sync::LazyLock;
+
+use crate::a2ac::{self, Span};
+
+/// Context lines around each hunk of `unified_diff`
+pub const DIFF_CONTEXT: usize = 3;
+
+// ============================================================================
+// EDITS
+// ============================================================================
+
+/// Replace `span` of the original message with `replacement`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Edit {
+    /// Id of the rule that proposed the edit
+    pub rule: String,
+    pub span: Span,
+    pub original: String,
+    pub replacement: String,
+}
+
+impl Edit {
+    /// Replace `span` verbatim
+    pub fn new(message: &str, span: Span, replacement: impl Into<String>) -> Self {
+        Edit {
+            rule: String::new(),
+            span,
+            original: message[span.start..span.end].to_string(),
+            replacement: replacement.into(),
+        }
+    }
+
+    /// Replace `span`, carrying over a leading capital ("Successfully completed" → "Complete")
+    pub fn replace(message: &str, span: Span, replacement: &str) -> Self {
+        let capitalized = message[span.start..]
+            .chars()
+            .next()
+            .is_some_and(char::is_uppercase);
+        let replacement = if capitalized {
+            capitalize(replacement)
+        } else {
+            replacement.to_string()
+        };
+        Edit::new(message, span, replacement)
+    }
+
+    /// Delete `span` and the spacing after it (or before it, at the end of a
+    /// clause); at a sentence start the next word is capitalized
+    pub fn remove(message: &str, span: Span) -> Self {
+        let after = &message[span.end..];
+        let trailing = after.len() - after.trim_start_matches([' ', '\t']).len();
+        let mut start = span.start;
+        let mut end = span.end + trailing;
+        let mut replacement = String::new();
+
+        if trailing == 0 {
+            let before = &message[..start];
+            start -= before.len() - before.trim_end_matches([' ', '\t']).len();
+        } else if at_sentence_start(message, span.start) {
+            if let Some(c) = message[end..].chars().next().filter(|c| c.is_lowercase()) {
+                replacement.extend(c.to_uppercase());
+                end += c.len_utf8();
+            }
+        }
+
+        Edit::new(message, Span::new(start, end), replacement)
+    }
+
+    /// Same edit, attributed to `rule`
+    pub fn with_rule(mut self, rule: &str) -> Self {
+        self.rule = rule.to_string();
+        self
+    }
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+fn at_sentence_start(message: &str, pos: usize) -> bool {
+    message[..pos]
+        .trim_end()
+        .chars()
+        .next_back()
+        .is_none_or(|c| matches!(c, '.' | '!' | '?' | ':'))
+}
+
+fn overlaps(a: Span, b: Span) -> bool {
+    a.start < b.end && b.start < a.end
+}
+
+/// Fenced code blocks (to EOF if unclosed), inline code, URLs and hex coordinates
+static PROTECTED: LazyLock<[Regex; 4]> = LazyLock::new(|| {
+    [
+        r"(?s)```.*?(```|\z)",
+        r"`[^`\n]+`",
+        r"\b[A-Za-z][A-Za-z0-9+.-]*://\S+",
+        r"(?i)\b0x600(:[0-9A-Za-z]+){4}\b",
+    ]
+    .map(|pattern| Regex::new(pattern).unwrap())
+});
+
+/// Ranges that must never be edited: code, A2AC chains, hex coordinates and URLs
+pub fn protected_spans(text: &str) -> Vec<Span> {
+    let mut spans: Vec<Span> = PROTECTED
+        .iter()
+        .flat_map(|re| {
+            re.find_iter(text)
+                .map(|m| Span::new(m.start(), m.end()))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+
+    spans.extend(a2ac::scan(text).into_iter().map(|chain| chain.span));
+    spans.sort_by_key(|s| s.start);
+    spans
+}
+
+// ============================================================================
+// REWRITE
+// ============================================================================
+
+/// A rewritten message and the edits that produced it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rewrite {
+    pub original: String,
+    pub text: String,
+    /// Applied edits, in message order, with spans into `original`
+    pub edits: Vec<Edit>,
+}
+
+impl Rewrite {
+    /// Apply proposed edits. Edits touching a protected range are dropped,
+    /// except a replacement of the whole message (a suggested coordinate
+    /// carries the URL over itself); of two overlapping edits the wider
+    /// wins, then the one proposed first.
+    pub fn apply(message: &str, proposed: Vec<Edit>) -> Self {
+        let protected = protected_spans(message);
+        let whole = Span::new(0, message.len());
+
+        let mut candidates: Vec<Edit> = proposed
+            .into_iter()
+            .filter(|e| !e.span.is_empty() || !e.replacement.is_empty())
+            .filter(|e| e.span == whole || !protected.iter().any(|p| overlaps(*p, e.span)))
+            .collect();
+        candidates.sort_by_key(|e| Reverse(e.span.len())); // Stable: proposal order breaks ties
+
+        let mut edits: Vec<Edit> = Vec::new();
+        for edit in candidates {
+            if !edits.iter().any(|e| overlaps(e.span, edit.span) || e.span == edit.span) {
+                edits.push(edit);
+            }
+        }
+        edits.sort_by_key(|e| e.span.start);
+
+        let mut text = String::with_capacity(message.len());
+        let mut pos = 0;
+        for edit in &edits {
+            text.push_str(&message[pos..edit.span.start]);
+            text.push_str(&edit.replacement);
+            pos = edit.span.end;
+        }
+        text.push_str(&message[pos..]);
+
+        Rewrite {
+            original: message.to_string(),
+            text,
+            edits,
+        }
+    }
+
+    pub fn is_changed(&self) -> bool {
+        self.text != self.original
+    }
+
+    /// Unified diff from the original to the rewritten message
+    pub fn diff(&self) -> String {
+        unified_diff(&self.original, &self.text, "original", "optimized")
+    }
+}
+
+// ============================================================================
+// UNIFIED DIFF
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Keep,
+    Delete,
+    Insert,
+}
+
+/// Line-based unified diff; empty when the texts are equal
+pub fn unified_diff(old: &str, new: &str, old_name: &str, new_name: &str) -> String {
+    if old == new {
+        return String::new();
+    }
+
+    let a: Vec<&str> = old.lines().collect();
+    let b: Vec<&str> = new.lines().collect();
+
+    // Longest common subsequence table, filled from the end
+    let mut lcs = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut ops: Vec<(Op, &str)> = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            ops.push((Op::Keep, a[i]));
+            i += 1;
+            j += 1;
+        } else if i < a.len() && (j == b.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            ops.push((Op::Delete, a[i]));
+            i += 1;
+        } else {
+            ops.push((Op::Insert, b[j]));
+            j += 1;
+        }
+    }
+
+    let changes: Vec<usize> = (0..ops.len()).filter(|&k| ops[k].0 != Op::Keep).collect();
+    let mut out = format!("--- {}\n+++ {}\n", old_name, new_name);
+    if changes.is_empty() {
+        // Texts differ only in trailing newlines
+        return out;
+    }
+
+    let mut k = 0;
+    while k < changes.len() {
+        // Grow the hunk while the next change is within two contexts
+        let mut last = k;
+        while last + 1 < changes.len() && changes[last + 1] - changes[last] <= 2 * DIFF_CONTEXT {
+            last += 1;
+        }
+        let start = changes[k].saturating_sub(DIFF_CONTEXT);
+        let end = (changes[last] + DIFF_CONTEXT + 1).min(ops.len());
+
+        let old_before = ops[..start].iter().filter(|(op, _)| *op != Op::Insert).count();
+        let new_before = ops[..start].iter().filter(|(op, _)| *op != Op::Delete).count();
+        let old_len = ops[start..end].iter().filter(|(op, _)| *op != Op::Insert).count();
+        let new_len = ops[start..end].iter().filter(|(op, _)| *op != Op::Delete).count();
+
+        out.push_str(&format!(
+            "@@ -{} +{} @@\n",
+            hunk_range(old_before, old_len),
+            hunk_range(new_before, new_len)
+        ));
+        for (op, line) in &ops[start..end] {
+            let prefix = match op {
+                Op::Keep => ' ',
+                Op::Delete => '-',
+                Op::Insert => '+',
+            };
+            out.push(prefix);
+            out.push_str(line);
+            out.push('\n');
+        }
+
+        k = last + 1;
+    }
+
+    out
+}
+
+fn hunk_range(before: usize, len: usize) -> String {
+    match len {
+        0 => format!("{},0", before),
+        1 => format!("{}", before + 1),
+        _ => format!("{},{}", before + 1, len),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remove_and_replace() {
+        let msg = "Let me check https://Example.com/AbC. Successfully completed, probably";
+        let edits = vec![
+            Edit::remove(msg, Span::new(0, 6)),
+            Edit::replace(msg, Span::new(38, 60), "complete"),
+            Edit::remove(msg, Span::new(62, 70)),
+            Edit::remove(msg, Span::new(13, 20)), // Inside the URL: dropped
+        ];
+        let rewrite = Rewrite::apply(msg, edits);
+
+        assert_eq!(rewrite.text, "Check https://Example.com/AbC. Complete,");
+        assert_eq!(rewrite.edits.len(), 3);
+        assert_eq!(rewrite.edits[0].original, "Let me c");
+        assert_eq!(rewrite.edits[0].replacement, "C");
+    }
+
+    #[test]
+    fn test_protected_spans() {
+        let msg = "probably fine ◈ RECEIPT:AbC `let probably = 1` ```\nprobably\n``` 0x600:04:CASE:RSLV:a1b2c3";
+        let protected = protected_spans(msg);
+        let covered = |needle: &str| {
+            let start = msg.find(needle).unwrap();
+            protected.iter().any(|p| p.start <= start && start + needle.len() <= p.end)
+        };
+
+        assert!(covered("◈ RECEIPT:AbC"));
+        assert!(covered("`let probably = 1`"));
+        assert!(covered("```\nprobably\n```"));
+        assert!(covered("0x600:04:CASE:RSLV:a1b2c3"));
+        assert!(!covered("probably fine"));
+    }
+
+    #[test]
+    fn test_unified_diff() {
+        assert_eq!(unified_diff("a\nb", "a\nb", "x", "y"), "");
+        let old: Vec<String> = (1..=20).map(|n| n.to_string()).collect();
+        let mut new = old.clone();
+        new[5] = "six".to_string();
+        new.push("21".to_string());
+        assert_eq!(
+            unified_diff(&old.join("\n"), &new.join("\n"), "original", "optimized"),
+            "--- original\n+++ optimized\n\
+             @@ -3,7 +3,7 @@\n 3\n 4\n 5\n-6\n+six\n 7\n 8\n 9\n\
+             @@ -18,3 +18,4 @@\n 18\n 19\n 20\n+21\n"
+        );
+        assert_eq!(
+            unified_diff("Let me check", "Check", "original", "optimized"),
+            "--- original\n+++ optimized\n@@ -1 +1 @@\n-Let me check\n+Check\n"
+        );
+    }
+}
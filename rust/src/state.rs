@@ -0,0 +1,87 @@
+// state.rs
+// Prior work loaded on bootstrap: every receipt per operation, in the order
+// the brain recorded them
+//
+// Keys are whatever the caller normalizes operations to (DayZero uses the
+// translator's operation key, so semantic and hex coordinates share a
+// history). A retry after a failure is appended, never overwritten, so a
+// failed attempt cannot hide a success and a success is not lost to a later
+// failed re-run.
+
+use std::collections::HashMap;
+
+use crate::receipt::Receipt;
+
+#[derive(Debug, Clone, Default)]
+pub struct StateCache {
+    history: HashMap<String, Vec<Receipt>>,
+    /// (key, index into its history), in insertion order
+    order: Vec<(String, usize)>,
+}
+
+impl StateCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append `receipt` to the history under `key`
+    pub fn insert(&mut self, key: String, receipt: Receipt) {
+        let history = self.history.entry(key.clone()).or_default();
+        self.order.push((key, history.len()));
+        history.push(receipt);
+    }
+
+    /// Every receipt under `key`, oldest first
+    pub fn history(&self, key: &str) -> &[Receipt] {
+        self.history.get(key).map_or(&[], Vec::as_slice)
+    }
+
+    /// The most recent receipt under `key`, successful or not
+    pub fn latest_attempt(&self, key: &str) -> Option<&Receipt> {
+        self.history(key).last()
+    }
+
+    /// The most recent successful receipt under `key`
+    pub fn latest_success(&self, key: &str) -> Option<&Receipt> {
+        self.history(key).iter().rev().find(|r| r.success)
+    }
+
+    /// Every cached receipt, all operations, in insertion order
+    pub fn receipts(&self) -> impl Iterator<Item = &Receipt> {
+        self.order.iter().map(|(key, i)| &self.history[key][*i])
+    }
+
+    /// Number of operations with at least one receipt
+    pub fn len(&self) -> usize {
+        self.history.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.history.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_history_keeps_every_attempt() {
+        let failed = Receipt::failure("git:push:repo", "git-agent-001", "trace_abc", "rejected");
+        let pushed = Receipt::success("git:push:repo", "git-agent-001", "trace_abc", "pushed");
+        let flaky = Receipt::failure("git:push:repo", "git-agent-001", "trace_abc", "timeout");
+
+        let mut cache = StateCache::new();
+        for receipt in [&failed, &pushed, &flaky] {
+            cache.insert("git:push".to_string(), receipt.clone());
+        }
+
+        assert_eq!(cache.history("git:push"), [failed, pushed.clone(), flaky.clone()]);
+        assert_eq!(cache.latest_attempt("git:push"), Some(&flaky));
+        assert_eq!(cache.latest_success("git:push"), Some(&pushed));
+        cache.insert("git:clone".to_string(), pushed.clone());
+        assert_eq!(cache.receipts().last(), Some(&pushed));
+        assert_eq!((cache.len(), cache.receipts().count()), (2, 4));
+        assert!(cache.history("git:tag").is_empty() && cache.latest_success("git:tag").is_none());
+    }
+}
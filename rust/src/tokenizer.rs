@@ -0,0 +1,350 @@
+// tokenizer.rs
+// Token counting: a byte-pair-encoding tokenizer over tiktoken rank files,
+// and the original words × 1.3 heuristic as a fallback
+//
+// Rank file format (one token per line):  <base64 token bytes> <rank>
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use regex::Regex;
+use std::collections::HashMap;
+use std::path::Path;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum TokenizerError {
+    #[error("rank file line {line}: {reason}")]
+    InvalidRank { line: usize, reason: String },
+
+    #[error("invalid split pattern: {0}")]
+    Pattern(#[from] regex::Error),
+
+    #[error("no known encoding for model '{0}'")]
+    UnknownModel(String),
+}
+
+pub trait Tokenizer: Send + Sync {
+    /// Encoding name, e.g. "cl100k_base"
+    fn name(&self) -> &str;
+
+    fn count_tokens(&self, text: &str) -> usize;
+}
+
+// ============================================================================
+// HEURISTIC
+// ============================================================================
+
+/// Whitespace words × 1.3; used when no rank file is available
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HeuristicTokenizer;
+
+impl Tokenizer for HeuristicTokenizer {
+    fn name(&self) -> &str {
+        "heuristic"
+    }
+
+    fn count_tokens(&self, text: &str) -> usize {
+        // Simplified token counting (GPT-style approximation)
+        let words = text.split_whitespace().count();
+        (words as f64 * 1.3) as usize // ~1.3 tokens per word average
+    }
+}
+
+// ============================================================================
+// ENCODINGS
+// ============================================================================
+
+/// tiktoken encodings and their pre-tokenization patterns
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Encoding {
+    R50kBase,
+    P50kBase,
+    Cl100kBase,
+    O200kBase,
+}
+
+// Split patterns without tiktoken's `\s+(?!\S)` alternative, which the
+// regex crate cannot express; `BpeTokenizer::split` reproduces it.
+const R50K_PATTERN: &str = r"'s|'t|'re|'ve|'m|'ll|'d| ?\p{L}+| ?\p{N}+| ?[^\s\p{L}\p{N}]+|\s+";
+const CL100K_PATTERN: &str = r"(?i:'s|'t|'re|'ve|'m|'ll|'d)|[^\r\n\p{L}\p{N}]?\p{L}+|\p{N}{1,3}| ?[^\s\p{L}\p{N}]+[\r\n]*|\s*[\r\n]+|\s+";
+const O200K_PATTERN: &str = concat!(
+    r"[^\r\n\p{L}\p{N}]?[\p{Lu}\p{Lt}\p{Lm}\p{Lo}\p{M}]*[\p{Ll}\p{Lm}\p{Lo}\p{M}]+(?i:'s|'t|'re|'ve|'m|'ll|'d)?",
+    r"|[^\r\n\p{L}\p{N}]?[\p{Lu}\p{Lt}\p{Lm}\p{Lo}\p{M}]+[\p{Ll}\p{Lm}\p{Lo}\p{M}]*(?i:'s|'t|'re|'ve|'m|'ll|'d)?",
+    r"|\p{N}{1,3}| ?[^\s\p{L}\p{N}]+[\r\n/]*|\s*[\r\n]+|\s+",
+);
+
+impl Encoding {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Encoding::R50kBase => "r50k_base",
+            Encoding::P50kBase => "p50k_base",
+            Encoding::Cl100kBase => "cl100k_base",
+            Encoding::O200kBase => "o200k_base",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "r50k_base" | "gpt2" => Some(Encoding::R50kBase),
+            "p50k_base" => Some(Encoding::P50kBase),
+            "cl100k_base" => Some(Encoding::Cl100kBase),
+            "o200k_base" => Some(Encoding::O200kBase),
+            _ => None,
+        }
+    }
+
+    /// Encoding a model family uses, by name prefix
+    pub fn for_model(model: &str) -> Option<Self> {
+        let prefixes = [
+            ("gpt-4o", Encoding::O200kBase),
+            ("gpt-4.1", Encoding::O200kBase),
+            ("o1", Encoding::O200kBase),
+            ("o3", Encoding::O200kBase),
+            ("o4", Encoding::O200kBase),
+            ("gpt-4", Encoding::Cl100kBase),
+            ("gpt-3.5", Encoding::Cl100kBase),
+            ("text-embedding-3", Encoding::Cl100kBase),
+            ("text-embedding-ada-002", Encoding::Cl100kBase),
+            ("text-davinci-003", Encoding::P50kBase),
+            ("text-davinci-002", Encoding::P50kBase),
+            ("code-davinci", Encoding::P50kBase),
+            ("davinci", Encoding::R50kBase),
+            ("gpt2", Encoding::R50kBase),
+        ];
+
+        Self::from_name(model).or_else(|| {
+            prefixes
+                .iter()
+                .find(|(prefix, _)| model.starts_with(prefix))
+                .map(|(_, encoding)| *encoding)
+        })
+    }
+
+    pub fn pattern(&self) -> &'static str {
+        match self {
+            Encoding::R50kBase | Encoding::P50kBase => R50K_PATTERN,
+            Encoding::Cl100kBase => CL100K_PATTERN,
+            Encoding::O200kBase => O200K_PATTERN,
+        }
+    }
+
+    /// File name tiktoken publishes the ranks under
+    pub fn file_name(&self) -> String {
+        format!("{}.tiktoken", self.name())
+    }
+}
+
+// ============================================================================
+// BYTE-PAIR ENCODING
+// ============================================================================
+
+pub struct BpeTokenizer {
+    name: String,
+    ranks: HashMap<Vec<u8>, u32>,
+    pattern: Regex,
+    /// Pattern has `\s*[\r\n]+`, so runs ending in a newline are kept whole
+    newline_runs: bool,
+}
+
+impl BpeTokenizer {
+    pub fn new(name: &str, ranks: HashMap<Vec<u8>, u32>, pattern: &str) -> Result<Self, TokenizerError> {
+        Ok(BpeTokenizer {
+            name: name.to_string(),
+            ranks,
+            pattern: Regex::new(pattern)?,
+            newline_runs: pattern.contains(r"\s*[\r\n]+"),
+        })
+    }
+
+    /// Tokenizer for `encoding` from tiktoken rank file contents
+    pub fn from_tiktoken(encoding: Encoding, ranks: &str) -> Result<Self, TokenizerError> {
+        Self::new(encoding.name(), parse_ranks(ranks)?, encoding.pattern())
+    }
+
+    /// Load `<encoding>.tiktoken` from a file
+    pub fn load(encoding: Encoding, path: impl AsRef<Path>) -> crate::Result<Self> {
+        Ok(Self::from_tiktoken(encoding, &std::fs::read_to_string(path)?)?)
+    }
+
+    /// Tokenizer for `model`, reading `<vocab_dir>/<encoding>.tiktoken`
+    pub fn for_model(model: &str, vocab_dir: impl AsRef<Path>) -> crate::Result<Self> {
+        let encoding =
+            Encoding::for_model(model).ok_or_else(|| TokenizerError::UnknownModel(model.to_string()))?;
+        Self::load(encoding, vocab_dir.as_ref().join(encoding.file_name()))
+    }
+
+    pub fn vocab_size(&self) -> usize {
+        self.ranks.len()
+    }
+
+    /// Pre-tokenize into the pieces BPE runs on
+    pub fn split<'t>(&self, text: &'t str) -> Vec<&'t str> {
+        let mut pieces = Vec::new();
+        let mut pos = 0;
+
+        while let Some(m) = self.pattern.find_at(text, pos) {
+            let mut end = m.end();
+            let piece = m.as_str();
+
+            // `\s+(?!\S)`: a whitespace run leaves its last char to the next piece
+            let newline_run = self.newline_runs && piece.ends_with(['\r', '\n']);
+            if !newline_run && piece.chars().all(char::is_whitespace) && end < text.len() {
+                if let Some(last) = piece.chars().next_back().filter(|_| piece.chars().count() > 1) {
+                    if !text[end..].starts_with(char::is_whitespace) {
+                        end -= last.len_utf8();
+                    }
+                }
+            }
+
+            pieces.push(&text[m.start()..end]);
+            pos = end;
+        }
+
+        pieces
+    }
+
+    /// Token ranks for `text`; bytes missing from the vocabulary count as rank `u32::MAX`
+    pub fn encode(&self, text: &str) -> Vec<u32> {
+        self.split(text)
+            .into_iter()
+            .flat_map(|piece| self.encode_piece(piece.as_bytes()))
+            .collect()
+    }
+
+    fn encode_piece(&self, piece: &[u8]) -> Vec<u32> {
+        if let Some(&rank) = self.ranks.get(piece) {
+            return vec![rank];
+        }
+
+        // Start from single bytes and merge the lowest-ranked adjacent pair,
+        // leftmost first on ties; `bounds` holds the part boundaries
+        let mut bounds: Vec<usize> = (0..=piece.len()).collect();
+        loop {
+            let best = (0..bounds.len().saturating_sub(2))
+                .filter_map(|i| {
+                    let rank = self.ranks.get(&piece[bounds[i]..bounds[i + 2]])?;
+                    Some((*rank, i))
+                })
+                .min();
+
+            match best {
+                Some((_, i)) => {
+                    bounds.remove(i + 1);
+                }
+                None => break,
+            }
+        }
+
+        bounds
+            .windows(2)
+            .map(|w| self.ranks.get(&piece[w[0]..w[1]]).copied().unwrap_or(u32::MAX))
+            .collect()
+    }
+}
+
+impl Tokenizer for BpeTokenizer {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn count_tokens(&self, text: &str) -> usize {
+        self.encode(text).len()
+    }
+}
+
+impl std::fmt::Debug for BpeTokenizer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BpeTokenizer")
+            .field("name", &self.name)
+            .field("vocab_size", &self.ranks.len())
+            .finish()
+    }
+}
+
+/// Parse tiktoken rank file contents
+pub fn parse_ranks(contents: &str) -> Result<HashMap<Vec<u8>, u32>, TokenizerError> {
+    let mut ranks = HashMap::new();
+
+    for (i, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let invalid = |reason: String| TokenizerError::InvalidRank { line: i + 1, reason };
+        let (token, rank) = line
+            .split_once(' ')
+            .ok_or_else(|| invalid("expected '<base64> <rank>'".to_string()))?;
+        let token = BASE64.decode(token).map_err(|e| invalid(e.to_string()))?;
+        let rank = rank.trim().parse().map_err(|e| invalid(format!("rank: {}", e)))?;
+        ranks.insert(token, rank);
+    }
+
+    Ok(ranks)
+}
+
+/// BPE for `model` from `vocab_dir`, or the heuristic when that is unavailable
+pub fn for_model_or_heuristic(model: &str, vocab_dir: impl AsRef<Path>) -> Box<dyn Tokenizer> {
+    match BpeTokenizer::for_model(model, vocab_dir) {
+        Ok(bpe) => Box::new(bpe),
+        Err(_) => Box::new(HeuristicTokenizer),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// All 256 bytes, then merges for "he", "ll", "hell", "hello", " w", " wo"
+    fn tiny_vocab() -> String {
+        let mut lines: Vec<String> = (0u32..256)
+            .map(|b| format!("{} {}", BASE64.encode([b as u8]), b))
+            .collect();
+        for (i, token) in ["he", "ll", "hell", "hello", " w", " wo"].iter().enumerate() {
+            lines.push(format!("{} {}", BASE64.encode(token), 256 + i));
+        }
+        lines.join("\n")
+    }
+
+    #[test]
+    fn test_split_whitespace_lookahead() {
+        let bpe = BpeTokenizer::from_tiktoken(Encoding::Cl100kBase, &tiny_vocab()).unwrap();
+        assert_eq!(bpe.split("hello   world"), ["hello", "  ", " world"]);
+        assert_eq!(bpe.split("I'll go\n\nnow  "), ["I", "'ll", " go", "\n\n", "now", "  "]);
+        assert_eq!(bpe.split("◈ 0x600:02"), ["◈", " ", "0", "x", "600", ":", "02"]);
+        assert_eq!(bpe.split("a\n\nb \n  c"), ["a", "\n\n", "b", " \n", " ", " c"]);
+
+        let r50k = BpeTokenizer::from_tiktoken(Encoding::R50kBase, &tiny_vocab()).unwrap();
+        assert_eq!(r50k.split("a\n\nb"), ["a", "\n", "\n", "b"]);
+    }
+
+    #[test]
+    fn test_bpe_merges() {
+        let bpe = BpeTokenizer::from_tiktoken(Encoding::Cl100kBase, &tiny_vocab()).unwrap();
+        assert_eq!(bpe.vocab_size(), 262);
+
+        // "hello" is one token, " world" merges " w" + "o" → " wo", then r, l, d
+        assert_eq!(bpe.encode("hello world"), [259, 261, b'r' as u32, b'l' as u32, b'd' as u32]);
+        assert_eq!(bpe.count_tokens("hello world"), 5);
+
+        // ◈ is three UTF-8 bytes with no merges: three tokens, not 1.3
+        assert_eq!(bpe.count_tokens("◈"), 3);
+
+        assert!(matches!(parse_ranks("aGk="), Err(TokenizerError::InvalidRank { line: 1, .. })));
+    }
+
+    #[test]
+    fn test_model_encodings() {
+        assert_eq!(Encoding::for_model("gpt-4o-mini"), Some(Encoding::O200kBase));
+        assert_eq!(Encoding::for_model("gpt-4-turbo"), Some(Encoding::Cl100kBase));
+        assert_eq!(Encoding::for_model("cl100k_base"), Some(Encoding::Cl100kBase));
+        assert_eq!(Encoding::for_model("llama"), None);
+        for encoding in [Encoding::R50kBase, Encoding::Cl100kBase, Encoding::O200kBase] {
+            assert!(Regex::new(encoding.pattern()).is_ok());
+        }
+
+        let fallback = for_model_or_heuristic("gpt-4o", "/nonexistent");
+        assert_eq!(fallback.name(), "heuristic");
+        assert_eq!(fallback.count_tokens("one two three four five six seven eight nine ten"), 13);
+    }
+}
@@ -0,0 +1,399 @@
+// translate.rs
+// Bidirectional mapping between A2AC(md) semantic coordinates and 0x600 hex
+// coordinates
+//
+//   ◈ case:resolve:5001a000001abcDEF  ⇄  0x600:02:CASE:RSLV:<state hash>
+//
+// Subjects map to entity codes and actions to action codes through a
+// loadable registry. The context becomes the state hash (first 6 hex chars of
+// its BLAKE3 hash); going back, the hash is kept as a `#hash` context so the
+// round trip is lossless.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use thiserror::Error;
+
+use crate::a2ac::{self, Coordinate, Expr, Span};
+use crate::hex_coordinate::{validate_code, HexCoordinate, HexCoordinateError, SpaceCode};
+
+/// Prefix marking a semantic context that is already a state hash
+pub const HASH_CONTEXT_PREFIX: char = '#';
+
+/// Length of state hashes derived from a semantic context
+pub const DERIVED_HASH_LENGTH: usize = 6;
+
+// ============================================================================
+// ERRORS
+// ============================================================================
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum TranslateError {
+    #[error("no entity code registered for subject '{0}'")]
+    UnknownSubject(String),
+
+    #[error("no action code registered for action '{0}'")]
+    UnknownAction(String),
+
+    #[error("no subject registered for entity code '{0}'")]
+    UnknownEntityCode(String),
+
+    #[error("no action registered for action code '{0}'")]
+    UnknownActionCode(String),
+
+    #[error("code '{code}' is mapped from both '{first}' and '{second}'")]
+    DuplicateCode {
+        code: String,
+        first: String,
+        second: String,
+    },
+
+    #[error("not a translatable coordinate: {0}")]
+    NotACoordinate(String),
+
+    #[error(transparent)]
+    Hex(#[from] HexCoordinateError),
+}
+
+// ============================================================================
+// REGISTRY
+// ============================================================================
+
+/// Subject ⇄ entity code and action ⇄ action code pairs.
+///
+/// JSON form:
+///
+/// ```json
+/// { "entities": { "case": "CASE" }, "actions": { "resolve": "RSLV" } }
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(try_from = "RegistryFile", into = "RegistryFile")]
+pub struct CoordinateRegistry {
+    entities: BTreeMap<String, String>,
+    actions: BTreeMap<String, String>,
+    entity_subjects: HashMap<String, String>,
+    action_names: HashMap<String, String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RegistryFile {
+    #[serde(default)]
+    entities: BTreeMap<String, String>,
+    #[serde(default)]
+    actions: BTreeMap<String, String>,
+}
+
+impl CoordinateRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The entity and action tables from the README
+    pub fn builtin() -> Self {
+        let mut registry = Self::new();
+        for (subject, entity) in [
+            ("case", "CASE"),
+            ("lead", "LEAD"),
+            ("account", "ACCT"),
+            ("knowledge", "KNOW"),
+            ("flow", "FLOW"),
+            ("model", "MODL"),
+        ] {
+            registry.insert_entity(subject, entity).expect("builtin entity");
+        }
+        for (action, code) in [
+            ("read", "READ"),
+            ("create", "CREA"),
+            ("update", "UPDT"),
+            ("delete", "DELE"),
+            ("resolve", "RSLV"),
+            ("escalate", "ESCL"),
+            ("infer", "INFE"),
+            ("sync", "SYNC"),
+        ] {
+            registry.insert_action(action, code).expect("builtin action");
+        }
+        registry
+    }
+
+    pub fn from_json(json: &str) -> crate::Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Load a JSON registry file
+    pub fn load(path: impl AsRef<Path>) -> crate::Result<Self> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    /// Map a subject to an entity code; codes must be unique
+    pub fn insert_entity(&mut self, subject: &str, entity: &str) -> Result<(), TranslateError> {
+        insert_pair(
+            &mut self.entities,
+            &mut self.entity_subjects,
+            subject,
+            entity,
+            "entity",
+        )
+    }
+
+    /// Map an action name to an action code; codes must be unique
+    pub fn insert_action(&mut self, action: &str, code: &str) -> Result<(), TranslateError> {
+        insert_pair(&mut self.actions, &mut self.action_names, action, code, "action")
+    }
+
+    /// Merge another registry's pairs into this one
+    pub fn extend(&mut self, other: &CoordinateRegistry) -> Result<(), TranslateError> {
+        for (subject, entity) in &other.entities {
+            self.insert_entity(subject, entity)?;
+        }
+        for (action, code) in &other.actions {
+            self.insert_action(action, code)?;
+        }
+        Ok(())
+    }
+
+    pub fn entity_for(&self, subject: &str) -> Option<&str> {
+        self.entities.get(&subject.to_lowercase()).map(String::as_str)
+    }
+
+    pub fn action_code_for(&self, action: &str) -> Option<&str> {
+        self.actions.get(&action.to_lowercase()).map(String::as_str)
+    }
+
+    pub fn subject_for(&self, entity: &str) -> Option<&str> {
+        self.entity_subjects.get(entity).map(String::as_str)
+    }
+
+    pub fn action_for(&self, code: &str) -> Option<&str> {
+        self.action_names.get(code).map(String::as_str)
+    }
+}
+
+fn insert_pair(
+    forward: &mut BTreeMap<String, String>,
+    reverse: &mut HashMap<String, String>,
+    name: &str,
+    code: &str,
+    field: &'static str,
+) -> Result<(), TranslateError> {
+    let name = name.to_lowercase();
+
+    // Codes follow the same 4-char rule as in a hex coordinate
+    validate_code(field, code)?;
+
+    if let Some(existing) = reverse.get(code) {
+        if *existing != name {
+            return Err(TranslateError::DuplicateCode {
+                code: code.to_string(),
+                first: existing.clone(),
+                second: name,
+            });
+        }
+    }
+
+    if let Some(old_code) = forward.insert(name.clone(), code.to_string()) {
+        reverse.remove(&old_code);
+    }
+    reverse.insert(code.to_string(), name);
+    Ok(())
+}
+
+impl TryFrom<RegistryFile> for CoordinateRegistry {
+    type Error = TranslateError;
+
+    fn try_from(file: RegistryFile) -> Result<Self, Self::Error> {
+        let mut registry = CoordinateRegistry::new();
+        for (subject, entity) in &file.entities {
+            registry.insert_entity(subject, entity)?;
+        }
+        for (action, code) in &file.actions {
+            registry.insert_action(action, code)?;
+        }
+        Ok(registry)
+    }
+}
+
+impl From<CoordinateRegistry> for RegistryFile {
+    fn from(registry: CoordinateRegistry) -> Self {
+        RegistryFile {
+            entities: registry.entities,
+            actions: registry.actions,
+        }
+    }
+}
+
+// ============================================================================
+// TRANSLATOR
+// ============================================================================
+
+#[derive(Debug, Clone, Default)]
+pub struct Translator {
+    registry: CoordinateRegistry,
+}
+
+impl Translator {
+    pub fn new(registry: CoordinateRegistry) -> Self {
+        Translator { registry }
+    }
+
+    pub fn registry(&self) -> &CoordinateRegistry {
+        &self.registry
+    }
+
+    /// `case:resolve:ctx` → `0x600:<space>:CASE:RSLV:<hash(ctx)>`
+    pub fn to_hex(&self, coord: &Coordinate, space: SpaceCode) -> Result<HexCoordinate, TranslateError> {
+        let entity = self
+            .registry
+            .entity_for(&coord.subject)
+            .ok_or_else(|| TranslateError::UnknownSubject(coord.subject.clone()))?;
+        let action = self
+            .registry
+            .action_code_for(&coord.action)
+            .ok_or_else(|| TranslateError::UnknownAction(coord.action.clone()))?;
+
+        let state_hash = match coord.context.as_deref() {
+            Some(context) => match context.strip_prefix(HASH_CONTEXT_PREFIX) {
+                Some(hash) => hash.to_string(),
+                None => state_hash(context),
+            },
+            None => state_hash(&format!("{}:{}", coord.subject, coord.action)),
+        };
+
+        Ok(HexCoordinate::new(space, entity, action, &state_hash)?)
+    }
+
+    /// `0x600:..:CASE:RSLV:a1b2c3` → `case:resolve:#a1b2c3`
+    pub fn to_semantic(&self, hex: &HexCoordinate) -> Result<Coordinate, TranslateError> {
+        let subject = self
+            .registry
+            .subject_for(hex.entity())
+            .ok_or_else(|| TranslateError::UnknownEntityCode(hex.entity().to_string()))?;
+        let action = self
+            .registry
+            .action_for(hex.action())
+            .ok_or_else(|| TranslateError::UnknownActionCode(hex.action().to_string()))?;
+
+        Ok(Coordinate {
+            subject: subject.to_string(),
+            action: action.to_string(),
+            context: Some(format!("{}{}", HASH_CONTEXT_PREFIX, hex.state_hash())),
+            span: Span::default(),
+        })
+    }
+
+    /// Translate a single coordinate message to the other dialect.
+    ///
+    /// `◈ subject:action:context` becomes an ACTION-space hex coordinate;
+    /// a hex coordinate becomes `◈ subject:action:#hash`.
+    pub fn translate(&self, message: &str) -> Result<String, TranslateError> {
+        let trimmed = message.trim();
+
+        if let Ok(hex) = trimmed.parse::<HexCoordinate>() {
+            return Ok(format!("{} {}", a2ac::MARKER, self.to_semantic(&hex)?));
+        }
+
+        match a2ac::parse(trimmed).map(|chain| chain.exprs) {
+            Ok(exprs) => match exprs.as_slice() {
+                [Expr::Coordinate(coord)] => Ok(self.to_hex(coord, SpaceCode::Action)?.to_string()),
+                _ => Err(TranslateError::NotACoordinate(trimmed.to_string())),
+            },
+            Err(_) => Err(TranslateError::NotACoordinate(trimmed.to_string())),
+        }
+    }
+
+    /// Dialect-independent key for an operation.
+    ///
+    /// Operations that map to a hex coordinate are keyed by its ACTION-space
+    /// form, so `case:resolve:x`, `◈ case:resolve:x` and the matching
+    /// `0x600:04:...` receipt coordinate all share one key. Anything else is
+    /// keyed by its trimmed text.
+    pub fn operation_key(&self, operation: &str) -> String {
+        let trimmed = operation.trim().trim_start_matches(a2ac::MARKER).trim();
+
+        if let Ok(hex) = trimmed.parse::<HexCoordinate>() {
+            return hex.in_space(SpaceCode::Action).to_string();
+        }
+
+        if let Ok(Expr::Coordinate(coord)) = a2ac::parse_expr(trimmed) {
+            if let Ok(hex) = self.to_hex(&coord, SpaceCode::Action) {
+                return hex.to_string();
+            }
+        }
+
+        trimmed.to_string()
+    }
+}
+
+/// State hash for a semantic context
+pub fn state_hash(context: &str) -> String {
+    blake3::hash(context.as_bytes()).to_hex()[..DERIVED_HASH_LENGTH].to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip_both_dialects() {
+        let translator = Translator::new(CoordinateRegistry::builtin());
+
+        let hex = translator.translate("◈ case:resolve:5001a000001abcDEF").unwrap();
+        let expected = format!("0x600:02:CASE:RSLV:{}", state_hash("5001a000001abcDEF"));
+        assert_eq!(hex, expected);
+
+        let semantic = translator.translate(&hex).unwrap();
+        assert_eq!(semantic, format!("◈ case:resolve:#{}", state_hash("5001a000001abcDEF")));
+        assert_eq!(translator.translate(&semantic).unwrap(), hex);
+
+        assert_eq!(
+            translator.translate("0x600:04:CASE:RSLV:a1b2c3").unwrap(),
+            "◈ case:resolve:#a1b2c3"
+        );
+    }
+
+    #[test]
+    fn test_loadable_registry() {
+        let mut registry = CoordinateRegistry::builtin();
+        let org = CoordinateRegistry::from_json(
+            r#"{ "entities": { "git": "REPO" }, "actions": { "clone": "CLON" } }"#,
+        )
+        .unwrap();
+        registry.extend(&org).unwrap();
+
+        let translator = Translator::new(registry);
+        let hex = translator.translate("◈ git:clone:repo").unwrap();
+        assert!(hex.starts_with("0x600:02:REPO:CLON:"));
+
+        // Both dialects share one operation key
+        assert_eq!(translator.operation_key("git:clone:repo"), hex);
+        assert_eq!(translator.operation_key(&hex.replace(":02:", ":04:")), hex);
+
+        assert!(matches!(
+            CoordinateRegistry::from_json(r#"{ "entities": { "voxel": "VOXEL" } }"#),
+            Err(crate::Error::Json(_))
+        ));
+        assert_eq!(
+            registry_with_dupe().unwrap_err(),
+            TranslateError::DuplicateCode { code: "CASE".into(), first: "case".into(), second: "ticket".into() }
+        );
+    }
+
+    fn registry_with_dupe() -> Result<(), TranslateError> {
+        CoordinateRegistry::builtin().insert_entity("ticket", "CASE")
+    }
+
+    #[test]
+    fn test_unknown_mappings() {
+        let translator = Translator::new(CoordinateRegistry::builtin());
+        assert_eq!(
+            translator.translate("◈ git:clone:repo"),
+            Err(TranslateError::UnknownSubject("git".into()))
+        );
+        assert_eq!(
+            translator.translate("0x600:02:CASE:ZZZZ:a1b2c3"),
+            Err(TranslateError::UnknownActionCode("ZZZZ".into()))
+        );
+        assert!(matches!(translator.translate("◈ RECEIPT:abc"), Err(TranslateError::NotACoordinate(_))));
+        assert_eq!(translator.operation_key("◈ git:clone:repo"), "git:clone:repo");
+    }
+}